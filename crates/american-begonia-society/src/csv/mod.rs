@@ -5,11 +5,34 @@ use serde::{
   Deserializer,
   Serialize,
 };
-use time::{macros::format_description, Date};
 
-use crate::{BegoniaType, Details, Get};
+use crate::{BegoniaType, Details};
 
-pub(super) struct CsvDetails(pub Details);
+/// Reads registrations written by [`write`].
+pub fn read(reader: impl std::io::Read) -> Result<Vec<Details>, csv::Error> {
+  csv::Reader::from_reader(reader)
+    .into_deserialize()
+    .map(|details| details.map(|CsvDetails(details)| details))
+    .collect()
+}
+
+/// Writes `details` with a header row, one registration per row.
+pub fn write<'a>(
+  writer: impl std::io::Write,
+  details: impl IntoIterator<Item = &'a Details>,
+) -> Result<(), csv::Error> {
+  let mut writer = csv::Writer::from_writer(writer);
+
+  for details in details {
+    writer.serialize(CsvDetails(details.clone()))?;
+  }
+
+  writer.flush()?;
+
+  Ok(())
+}
+
+struct CsvDetails(Details);
 
 impl Serialize for CsvDetails {
   fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -136,33 +159,34 @@ impl<'de> Deserialize<'de> for CsvDetails {
   }
 }
 
+#[cfg(test)]
 mod tests {
   use super::*;
 
   const CSV: &str = "id,name,ty,parents,hybridizer,location,origin,pub_date\n94,Mottled Sheen,rhizome,Joe Hayden x bowerae,Don Horton,USA - CA,1952,1954 Sep p. 209\n";
 
+  #[cfg(feature = "scrape")]
   #[tokio::test]
   async fn to_csv() {
-    let details = Get { id: 94 }.lookup().await.unwrap();
-    let details = CsvDetails(details);
+    let details = crate::Get { id: 94 }.lookup().await.unwrap();
 
-    let mut writer = csv::Writer::from_writer(Vec::new());
-    writer.serialize(details).unwrap();
+    let mut written = Vec::new();
+    write(&mut written, [&details]).unwrap();
 
-    let s = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+    let s = String::from_utf8(written).unwrap();
 
     assert_eq!(CSV, s);
   }
 
-  #[tokio::test]
-  async fn from_csv() {
-    let mut reader = csv::Reader::from_reader(CSV.as_bytes());
-    let data = reader
-      .deserialize::<CsvDetails>()
-      .map(|d| d.map(|d| d.0))
-      .collect::<Result<Vec<Details>, _>>()
-      .unwrap();
-
-    dbg!(data);
+  #[test]
+  fn from_csv() {
+    let data = read(CSV.as_bytes()).unwrap();
+
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0].name, "Mottled Sheen");
+
+    let mut written = Vec::new();
+    write(&mut written, &data).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), CSV);
   }
 }
@@ -0,0 +1,106 @@
+use std::{io::Write, sync::Arc};
+
+use icra_export::{
+  arrow_array::{
+    builder::{ListBuilder, StringBuilder},
+    ArrayRef,
+    StringArray,
+    UInt32Array,
+  },
+  arrow_schema::{DataType, Field},
+  Error,
+};
+
+use crate::Details;
+
+/// Writes each cultivar as a JSON object on its own line, in the order given.
+pub fn write_jsonl<'a>(
+  details: impl IntoIterator<Item = &'a Details>,
+  writer: impl Write,
+) -> Result<(), Error> {
+  icra_export::write_jsonl(details, writer)
+}
+
+/// Writes cultivars to Parquet in the order given, with types as a list of the names the
+/// register uses, like `cane-like`.
+pub fn write_parquet<'a>(
+  details: impl IntoIterator<Item = &'a Details>,
+  writer: impl Write + Send,
+) -> Result<(), Error> {
+  let details = details.into_iter().collect::<Vec<_>>();
+
+  let string = |value: fn(&Details) -> &str| -> ArrayRef {
+    Arc::new(StringArray::from_iter_values(
+      details.iter().map(|d| value(d)),
+    ))
+  };
+
+  let mut ty = ListBuilder::new(StringBuilder::new());
+  for d in &details {
+    ty.append_value(d.ty.iter().map(|ty| Some(ty.to_string())));
+  }
+  let ty: ArrayRef = Arc::new(ty.finish());
+
+  icra_export::write_parquet(
+    vec![
+      (
+        Field::new("id", DataType::UInt32, false),
+        Arc::new(UInt32Array::from_iter_values(details.iter().map(|d| d.id))),
+      ),
+      (
+        Field::new("name", DataType::Utf8, false),
+        string(|d| &d.name),
+      ),
+      (Field::new("ty", ty.data_type().clone(), false), ty),
+      (
+        Field::new("parents", DataType::Utf8, false),
+        string(|d| &d.parents),
+      ),
+      (
+        Field::new("hybridizer", DataType::Utf8, false),
+        string(|d| &d.hybridizer),
+      ),
+      (
+        Field::new("location", DataType::Utf8, false),
+        string(|d| &d.location),
+      ),
+      (
+        Field::new("origin", DataType::Utf8, false),
+        string(|d| &d.origin),
+      ),
+      (
+        Field::new("pub_date", DataType::Utf8, false),
+        string(|d| &d.pub_date),
+      ),
+    ],
+    writer,
+  )
+}
+
+#[test]
+fn export() -> Result<(), Error> {
+  use crate::BegoniaType;
+
+  let details = [Details {
+    id: 94,
+    name: "Mottled Sheen".into(),
+    ty: vec![BegoniaType::Rhizome, BegoniaType::CaneLike],
+    parents: "Joe Hayden x bowerae".into(),
+    hybridizer: "Don Horton".into(),
+    location: "USA - CA".into(),
+    origin: "1952".into(),
+    pub_date: "1954 Sep p. 209".into(),
+  }];
+
+  let mut jsonl = Vec::new();
+  write_jsonl(&details, &mut jsonl)?;
+  assert!(String::from_utf8(jsonl)
+    .unwrap()
+    .contains("\"ty\":[\"rhizome\",\"canelike\"]"));
+
+  let mut parquet = Vec::new();
+  write_parquet(&details, &mut parquet)?;
+  assert!(parquet.starts_with(b"PAR1"));
+
+  Ok(())
+}
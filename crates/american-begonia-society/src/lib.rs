@@ -1,15 +1,21 @@
+#[cfg(feature = "scrape")]
 use std::{
   collections::HashMap,
-  fmt::Display,
   num::{IntErrorKind, ParseIntError},
-  str::FromStr,
 };
+use std::{fmt::Display, str::FromStr};
 
+#[cfg(feature = "scrape")]
+pub use icra_http::Client;
+#[cfg(feature = "scrape")]
 use scraper::{Html, Selector};
-use time::{macros::format_description, Date};
 
-mod csv;
+#[cfg(feature = "csv")]
+pub mod csv;
+#[cfg(feature = "export")]
+pub mod export;
 
+#[cfg(feature = "scrape")]
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
   #[error("Not found")]
@@ -22,13 +28,15 @@ pub enum Error {
   Time(#[from] time::error::Parse),
 }
 
-#[derive(Clone, Copy, Debug, Default, serde::Serialize)]
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Get {
   pub id: u32,
 }
 
-#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq, Hash, serde::Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum BegoniaType {
   CaneLike,
   RexCultorum,
@@ -97,13 +105,15 @@ impl Display for BegoniaType {
   }
 }
 
-#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq, Hash, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Synonym {
   pub genus: String,
   pub epithet: Option<String>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Details {
   pub id: u32,
   pub name: String,
@@ -115,13 +125,15 @@ pub struct Details {
   pub pub_date: String,
 }
 
+#[cfg(feature = "scrape")]
 impl Get {
   pub async fn lookup(&self) -> Result<Details, Error> {
-    let client = reqwest::Client::new();
+    self.lookup_with(icra_http::Client::shared()).await
+  }
 
+  pub async fn lookup_with(&self, client: &icra_http::Client) -> Result<Details, Error> {
     let res = client
-      .get("https://www.begonias.org/cultivar-preservation/registered-cultivars/")
-      .send()
+      .send(client.get("https://www.begonias.org/cultivar-preservation/registered-cultivars/"))
       .await?;
 
     let html = res.text().await?;
@@ -132,12 +144,25 @@ impl Get {
   }
 }
 
+#[cfg(feature = "blocking")]
+impl Get {
+  pub fn lookup_blocking(&self) -> Result<Details, Error> {
+    icra_http::block_on(self.lookup())
+  }
+
+  pub fn lookup_blocking_with(&self, client: &icra_http::Client) -> Result<Details, Error> {
+    icra_http::block_on(self.lookup_with(client))
+  }
+}
+
+#[cfg(feature = "scrape")]
 fn to_string(el: scraper::ElementRef<'_>) -> String {
   let text = el.text().map(|t| t.to_string()).collect::<String>();
 
   text.trim().to_string()
 }
 
+#[cfg(feature = "scrape")]
 fn parse(id: u32, html: Html) -> Result<Details, Error> {
   let table = Selector::parse("table > tbody").unwrap();
 
@@ -198,6 +223,7 @@ fn parse(id: u32, html: Html) -> Result<Details, Error> {
   Ok(details)
 }
 
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn get_id() {
   let id = Get { id: 94 };
@@ -0,0 +1,152 @@
+use std::{io::Write, sync::Arc};
+
+pub use arrow_array;
+use arrow_array::{ArrayRef, RecordBatch, StructArray};
+use arrow_buffer::NullBuffer;
+pub use arrow_schema;
+use arrow_schema::{Field, Schema};
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+use time::{Date, OffsetDateTime};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+  #[error(transparent)]
+  Io(#[from] std::io::Error),
+  #[error(transparent)]
+  Json(#[from] serde_json::Error),
+  #[error(transparent)]
+  Arrow(#[from] arrow_schema::ArrowError),
+  #[error(transparent)]
+  Parquet(#[from] parquet::errors::ParquetError),
+}
+
+/// Writes one JSON object per line, in the records' own serde shape.
+pub fn write_jsonl<'a, T: serde::Serialize + 'a>(
+  records: impl IntoIterator<Item = &'a T>,
+  mut writer: impl Write,
+) -> Result<(), Error> {
+  for record in records {
+    serde_json::to_writer(&mut writer, record)?;
+    writer.write_all(b"\n")?;
+  }
+
+  writer.flush()?;
+
+  Ok(())
+}
+
+/// Writes `columns` as a single snappy-compressed Parquet row group. Each field must have the
+/// same data type and length as its column.
+pub fn write_parquet(
+  columns: Vec<(Field, ArrayRef)>,
+  writer: impl Write + Send,
+) -> Result<(), Error> {
+  let (fields, arrays): (Vec<_>, Vec<_>) = columns.into_iter().unzip();
+  let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?;
+
+  let props = WriterProperties::builder()
+    .set_compression(Compression::SNAPPY)
+    .build();
+
+  let mut writer = ArrowWriter::try_new(writer, batch.schema(), Some(props))?;
+  writer.write(&batch)?;
+  writer.close()?;
+
+  Ok(())
+}
+
+/// A struct column from `children`, null in each row where `valid` is false.
+pub fn struct_array(
+  children: Vec<(Field, ArrayRef)>,
+  valid: impl IntoIterator<Item = bool>,
+) -> Result<ArrayRef, Error> {
+  let (fields, arrays): (Vec<_>, Vec<_>) = children.into_iter().unzip();
+  let nulls = NullBuffer::from_iter(valid);
+
+  Ok(Arc::new(StructArray::try_new(
+    fields.into(),
+    arrays,
+    Some(nulls),
+  )?))
+}
+
+/// Days since the Unix epoch, as stored in a `Date32` column.
+pub fn date32(date: Date) -> i32 {
+  date.to_julian_day() - OffsetDateTime::UNIX_EPOCH.date().to_julian_day()
+}
+
+#[test]
+fn export() -> Result<(), Error> {
+  use arrow_array::{Array, Date32Array, StringArray, UInt32Array};
+  use arrow_schema::DataType;
+  use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+  use time::Month;
+
+  #[derive(serde::Serialize)]
+  struct Record {
+    id: u32,
+    name: Option<&'static str>,
+    date: Date,
+  }
+
+  let date = Date::from_calendar_date(1927, Month::March, 4).unwrap();
+  let records = [
+    Record {
+      id: 1,
+      name: Some("Elisabethae"),
+      date,
+    },
+    Record {
+      id: 2,
+      name: None,
+      date: OffsetDateTime::UNIX_EPOCH.date(),
+    },
+  ];
+
+  let mut jsonl = Vec::new();
+  write_jsonl(&records, &mut jsonl)?;
+  assert_eq!(
+    String::from_utf8(jsonl).unwrap(),
+    "{\"id\":1,\"name\":\"Elisabethae\",\"date\":\"1927-03-04\"}\n{\"id\":2,\"name\":null,\"date\":\"1970-01-01\"}\n"
+  );
+
+  let mut parquet = Vec::new();
+  write_parquet(
+    vec![
+      (
+        Field::new("id", DataType::UInt32, false),
+        Arc::new(UInt32Array::from_iter_values(records.iter().map(|r| r.id))),
+      ),
+      (
+        Field::new("name", DataType::Utf8, true),
+        Arc::new(StringArray::from_iter(records.iter().map(|r| r.name))),
+      ),
+      (
+        Field::new("date", DataType::Date32, false),
+        Arc::new(Date32Array::from_iter_values(
+          records.iter().map(|r| date32(r.date)),
+        )),
+      ),
+    ],
+    &mut parquet,
+  )?;
+
+  let batch = ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(parquet))?
+    .build()?
+    .next()
+    .unwrap()?;
+
+  assert_eq!(batch.num_rows(), 2);
+  assert_eq!(batch.schema().field(2).data_type(), &DataType::Date32);
+
+  let dates = batch
+    .column(2)
+    .as_any()
+    .downcast_ref::<Date32Array>()
+    .unwrap();
+  assert_eq!(dates.value(0), -15644);
+  assert_eq!(dates.value(1), 0);
+  assert!(batch.column(1).is_null(1));
+
+  Ok(())
+}
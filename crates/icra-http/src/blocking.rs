@@ -0,0 +1,45 @@
+use std::{future::Future, sync::OnceLock};
+
+use tokio::runtime::{Builder, Runtime};
+
+/// Runs `future` to completion on a runtime shared by every blocking call, so connections pooled
+/// by [`crate::Client::shared`] stay usable between calls.
+///
+/// # Panics
+///
+/// When called from within an async runtime.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+  static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+  RUNTIME
+    .get_or_init(|| {
+      Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("icra-http-blocking")
+        .enable_all()
+        .build()
+        .expect("blocking runtime")
+    })
+    .block_on(future)
+}
+
+#[test]
+fn from_threads() {
+  let handles = (0..4)
+    .map(|i| {
+      std::thread::spawn(move || {
+        block_on(async move {
+          tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+          i
+        })
+      })
+    })
+    .collect::<Vec<_>>();
+
+  let results = handles
+    .into_iter()
+    .map(|handle| handle.join().unwrap())
+    .collect::<Vec<_>>();
+
+  assert_eq!(results, [0, 1, 2, 3]);
+}
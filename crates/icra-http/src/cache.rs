@@ -0,0 +1,341 @@
+use std::{
+  ffi::OsString,
+  path::{Path, PathBuf},
+  sync::atomic::{AtomicU64, Ordering},
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::{
+  header::{
+    HeaderMap,
+    HeaderName,
+    HeaderValue,
+    ETAG,
+    IF_MODIFIED_SINCE,
+    IF_NONE_MATCH,
+    LAST_MODIFIED,
+  },
+  Method,
+  Request,
+  Response,
+  ResponseBuilderExt,
+  StatusCode,
+  Url,
+};
+use sha2::{Digest, Sha256};
+
+#[derive(Clone, Debug)]
+pub struct CacheConfig {
+  pub dir: PathBuf,
+  /// Entries younger than this are served without asking the server at all.
+  /// Older ones are revalidated with `If-None-Match`/`If-Modified-Since`.
+  pub ttl: Duration,
+}
+
+impl CacheConfig {
+  pub fn new(dir: impl Into<PathBuf>) -> Self {
+    Self {
+      dir: dir.into(),
+      ttl: Duration::from_secs(60 * 60 * 24),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Entry {
+  url: String,
+  etag: Option<String>,
+  last_modified: Option<String>,
+  headers: Vec<(String, String)>,
+  /// SHA-256 of the body file, which is written separately and may belong to another write.
+  body_sha256: String,
+  /// Seconds since the epoch when the server last confirmed this body.
+  fetched: u64,
+}
+
+#[derive(Debug)]
+pub(crate) struct Cached {
+  key: String,
+  entry: Entry,
+  body: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Cache {
+  config: CacheConfig,
+}
+
+impl Cache {
+  pub fn new(config: CacheConfig) -> Self {
+    Self { config }
+  }
+
+  /// `GET`s and `POST`s (like the register's search forms) are cached, keyed by method, full
+  /// URL (including the query) and encoded body. Streamed bodies can't be keyed, so aren't.
+  pub fn key(request: &Request) -> Option<String> {
+    if request.method() != Method::GET && request.method() != Method::POST {
+      return None;
+    }
+
+    let body = match request.body() {
+      Some(body) => body.as_bytes()?,
+      None => &[],
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(request.method().as_str());
+    hasher.update(b" ");
+    hasher.update(request.url().as_str());
+    hasher.update(b"\n");
+    hasher.update(body);
+
+    Some(hex::encode(hasher.finalize()))
+  }
+
+  fn paths(&self, key: &str) -> (PathBuf, PathBuf) {
+    let dir = self.config.dir.join(&key[..2]);
+
+    (
+      dir.join(format!("{key}.json")),
+      dir.join(format!("{key}.body")),
+    )
+  }
+
+  /// A missing or unreadable entry is treated as a miss, as is a body that doesn't match its
+  /// metadata.
+  pub async fn load(&self, key: &str) -> Option<Cached> {
+    let (meta, body) = self.paths(key);
+
+    let entry: Entry = serde_json::from_slice(&tokio::fs::read(meta).await.ok()?).ok()?;
+    let body = tokio::fs::read(body).await.ok()?;
+
+    if entry.body_sha256 != sha256(&body) {
+      return None;
+    }
+
+    Some(Cached {
+      key: key.to_string(),
+      entry,
+      body,
+    })
+  }
+
+  pub async fn store(&self, key: &str, url: &Url, headers: &HeaderMap, body: &[u8]) {
+    let header = |name| {
+      headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+    };
+
+    let entry = Entry {
+      url: url.to_string(),
+      etag: header(ETAG),
+      last_modified: header(LAST_MODIFIED),
+      headers: headers
+        .iter()
+        .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+        .collect(),
+      body_sha256: sha256(body),
+      fetched: now(),
+    };
+
+    // a failed write only costs a refetch next time
+    let _ = self.write(key, &entry, Some(body)).await;
+  }
+
+  /// Marks an entry as confirmed by a `304 Not Modified`.
+  pub async fn refresh(&self, cached: &mut Cached) {
+    cached.entry.fetched = now();
+
+    let _ = self.write(&cached.key, &cached.entry, None).await;
+  }
+
+  /// The metadata goes last, so a reader never sees it before the body it describes.
+  async fn write(&self, key: &str, entry: &Entry, body: Option<&[u8]>) -> std::io::Result<()> {
+    let (meta, body_path) = self.paths(key);
+    tokio::fs::create_dir_all(meta.parent().unwrap_or(Path::new("."))).await?;
+
+    if let Some(body) = body {
+      write_atomic(&body_path, body).await?;
+    }
+
+    write_atomic(&meta, &serde_json::to_vec(entry)?).await
+  }
+
+  pub fn is_fresh(&self, cached: &Cached) -> bool {
+    now().saturating_sub(cached.entry.fetched) < self.config.ttl.as_secs()
+  }
+}
+
+impl Cached {
+  pub fn add_conditions(&self, request: &mut Request) {
+    let headers = request.headers_mut();
+
+    if let Some(etag) = self.entry.etag.as_deref().and_then(|v| v.parse().ok()) {
+      headers.insert(IF_NONE_MATCH, etag);
+    }
+
+    if let Some(date) = self
+      .entry
+      .last_modified
+      .as_deref()
+      .and_then(|v| v.parse().ok())
+    {
+      headers.insert(IF_MODIFIED_SINCE, date);
+    }
+  }
+
+  pub fn to_response(&self) -> Response {
+    let headers = self
+      .entry
+      .headers
+      .iter()
+      .filter_map(|(k, v)| {
+        Some((
+          HeaderName::try_from(k.as_str()).ok()?,
+          HeaderValue::try_from(v.as_str()).ok()?,
+        ))
+      })
+      .collect();
+
+    response(Url::parse(&self.entry.url).ok(), headers, self.body.clone())
+  }
+}
+
+/// Rebuilds a `200 OK` response around a body that has already been read.
+pub(crate) fn response(
+  url: Option<Url>,
+  headers: HeaderMap,
+  body: impl Into<reqwest::Body>,
+) -> Response {
+  let mut builder = http::Response::builder().status(StatusCode::OK);
+  if let Some(url) = url {
+    builder = builder.url(url);
+  }
+
+  let mut response = builder.body(body.into()).expect("valid response");
+  *response.headers_mut() = headers;
+
+  response.into()
+}
+
+/// Writes to a temporary file of its own before renaming it over `path`, so concurrent writes
+/// of the same entry can't rename each other's half-written files into place.
+async fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+  static NEXT: AtomicU64 = AtomicU64::new(0);
+
+  let mut tmp = OsString::from(path.as_os_str());
+  tmp.push(format!(
+    ".{}.{}.tmp",
+    std::process::id(),
+    NEXT.fetch_add(1, Ordering::Relaxed)
+  ));
+
+  if let Err(e) = tokio::fs::write(&tmp, contents).await {
+    let _ = tokio::fs::remove_file(&tmp).await;
+    return Err(e);
+  }
+
+  tokio::fs::rename(tmp, path).await
+}
+
+fn sha256(bytes: &[u8]) -> String {
+  hex::encode(Sha256::digest(bytes))
+}
+
+fn now() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap_or_default()
+    .as_secs()
+}
+
+#[tokio::test]
+async fn round_trip() {
+  let dir = tempfile::tempdir().unwrap();
+
+  let cache = Cache::new(CacheConfig::new(dir.path()));
+  let client = reqwest::Client::new();
+
+  let get = client
+    .get("https://apps.rhs.org.uk/horticulturaldatabase/orchidregister/orchiddetails.asp")
+    .query(&[("ID", 1234)])
+    .build()
+    .unwrap();
+  let other = client
+    .get("https://apps.rhs.org.uk/horticulturaldatabase/orchidregister/orchiddetails.asp")
+    .query(&[("ID", 1235)])
+    .build()
+    .unwrap();
+  let post = |genus: &str| {
+    client
+      .post("https://apps.rhs.org.uk/horticulturaldatabase/orchidregister/orchidresults.asp")
+      .form(&[("genus", genus)])
+      .build()
+      .unwrap()
+  };
+  let delete = client
+    .delete("https://apps.rhs.org.uk/horticulturaldatabase/orchidregister/orchiddetails.asp")
+    .build()
+    .unwrap();
+
+  let key = Cache::key(&get).unwrap();
+  assert_ne!(Cache::key(&other), Some(key.clone()));
+  assert_eq!(Cache::key(&post("Cattleya")), Cache::key(&post("Cattleya")));
+  assert_ne!(Cache::key(&post("Cattleya")), Cache::key(&post("Vanda")));
+  assert_eq!(Cache::key(&delete), None);
+  assert!(cache.load(&key).await.is_none());
+
+  let mut headers = HeaderMap::new();
+  headers.insert(ETAG, HeaderValue::from_static("\"abc\""));
+  headers.insert(
+    LAST_MODIFIED,
+    HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+  );
+  cache
+    .store(&key, get.url(), &headers, b"<html></html>")
+    .await;
+
+  let mut cached = cache.load(&key).await.unwrap();
+  assert!(cache.is_fresh(&cached));
+
+  let mut request = get.try_clone().unwrap();
+  cached.add_conditions(&mut request);
+  assert_eq!(request.headers()[IF_NONE_MATCH], "\"abc\"");
+  assert_eq!(
+    request.headers()[IF_MODIFIED_SINCE],
+    "Wed, 21 Oct 2015 07:28:00 GMT"
+  );
+
+  cached.entry.fetched = 0;
+  assert!(!cache.is_fresh(&cached));
+  cache.refresh(&mut cached).await;
+  assert!(cache.is_fresh(&cache.load(&key).await.unwrap()));
+
+  let response = cached.to_response();
+  assert_eq!(response.url(), get.url());
+  assert_eq!(response.headers()[ETAG], "\"abc\"");
+  assert_eq!(response.text().await.unwrap(), "<html></html>");
+
+  let bodies = ["a", "b", "c"].map(|body| body.repeat(100_000));
+  tokio::join!(
+    cache.store(&key, get.url(), &headers, bodies[0].as_bytes()),
+    cache.store(&key, get.url(), &headers, bodies[1].as_bytes()),
+    cache.store(&key, get.url(), &headers, bodies[2].as_bytes()),
+  );
+
+  let body = cache.load(&key).await.unwrap().body;
+  assert!(bodies.iter().any(|b| b.as_bytes() == body));
+
+  // a body swapped in under another write's metadata
+  let (_, body_path) = cache.paths(&key);
+  std::fs::write(&body_path, "torn").unwrap();
+  assert!(cache.load(&key).await.is_none());
+
+  let files = std::fs::read_dir(dir.path().join(&key[..2]))
+    .unwrap()
+    .map(|file| file.unwrap().file_name())
+    .collect::<Vec<_>>();
+  assert_eq!(files.len(), 2);
+}
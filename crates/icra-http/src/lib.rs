@@ -0,0 +1,182 @@
+use std::{
+  sync::{Arc, OnceLock},
+  time::Duration,
+};
+
+use reqwest::{IntoUrl, Request, RequestBuilder, Response, StatusCode};
+
+#[cfg(feature = "blocking")]
+mod blocking;
+mod cache;
+mod rate_limit;
+mod retry;
+
+#[cfg(feature = "blocking")]
+pub use blocking::block_on;
+use cache::Cache;
+pub use cache::CacheConfig;
+pub use rate_limit::RateLimiter;
+pub use retry::is_transient;
+
+#[derive(Clone, Debug)]
+pub struct Config {
+  pub user_agent: String,
+  /// Sustained request rate allowed for each host.
+  pub requests_per_second: f64,
+  pub burst: u32,
+  pub connect_timeout: Duration,
+  /// Limit on a whole request, from connecting until the body is read.
+  pub timeout: Duration,
+  /// How long pooled connections are kept open between requests.
+  pub pool_idle_timeout: Duration,
+  pub retries: u32,
+  pub backoff: Duration,
+  pub max_backoff: Duration,
+  /// Keeps `GET` and form `POST` responses on disk and revalidates them instead of refetching.
+  pub cache: Option<CacheConfig>,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      user_agent: concat!(
+        "icra/",
+        env!("CARGO_PKG_VERSION"),
+        " (+https://github.com/callym/icra)"
+      )
+      .to_string(),
+      requests_per_second: 4.0,
+      burst: 4,
+      connect_timeout: Duration::from_secs(10),
+      timeout: Duration::from_secs(30),
+      pool_idle_timeout: Duration::from_secs(90),
+      retries: 5,
+      backoff: Duration::from_millis(500),
+      max_backoff: Duration::from_secs(60),
+      cache: None,
+    }
+  }
+}
+
+/// Cheap to clone; clones share the connection pool and rate limits.
+#[derive(Clone, Debug)]
+pub struct Client {
+  inner: reqwest::Client,
+  config: Arc<Config>,
+  limiter: Arc<RateLimiter>,
+  cache: Option<Arc<Cache>>,
+}
+
+impl Client {
+  pub fn new(config: Config) -> Result<Self, reqwest::Error> {
+    let inner = reqwest::Client::builder()
+      .user_agent(&config.user_agent)
+      .connect_timeout(config.connect_timeout)
+      .timeout(config.timeout)
+      .pool_idle_timeout(config.pool_idle_timeout)
+      .tcp_keepalive(config.pool_idle_timeout)
+      .build()?;
+
+    Ok(Self {
+      inner,
+      limiter: Arc::new(RateLimiter::new(config.requests_per_second, config.burst)),
+      cache: config.cache.clone().map(|c| Arc::new(Cache::new(c))),
+      config: Arc::new(config),
+    })
+  }
+
+  /// The client used by lookups that aren't given one explicitly.
+  pub fn shared() -> &'static Client {
+    static SHARED: OnceLock<Client> = OnceLock::new();
+
+    SHARED.get_or_init(|| Client::new(Config::default()).expect("default HTTP client"))
+  }
+
+  pub fn config(&self) -> &Config {
+    &self.config
+  }
+
+  pub fn get(&self, url: impl IntoUrl) -> RequestBuilder {
+    self.inner.get(url)
+  }
+
+  pub fn post(&self, url: impl IntoUrl) -> RequestBuilder {
+    self.inner.post(url)
+  }
+
+  /// Sends `request` once the host's rate limit allows, retrying 429s, 5xxs and
+  /// connection failures. The last response is returned as-is, so callers still
+  /// need to check its status.
+  ///
+  /// With a cache configured, fresh cached requests are answered locally and stale
+  /// ones are sent as conditional requests; a `304` is answered from the cache.
+  pub async fn send(&self, request: RequestBuilder) -> Result<Response, reqwest::Error> {
+    let mut request = request.build()?;
+
+    let Some((cache, key)) = self
+      .cache
+      .as_deref()
+      .and_then(|cache| Some((cache, Cache::key(&request)?)))
+    else {
+      return self.execute(request).await;
+    };
+
+    let mut cached = cache.load(&key).await;
+
+    if let Some(cached) = &cached {
+      if cache.is_fresh(cached) {
+        return Ok(cached.to_response());
+      }
+
+      cached.add_conditions(&mut request);
+    }
+
+    let res = self.execute(request).await?;
+
+    match (res.status(), cached.as_mut()) {
+      (StatusCode::NOT_MODIFIED, Some(cached)) => {
+        cache.refresh(cached).await;
+        Ok(cached.to_response())
+      },
+      (StatusCode::OK, _) => {
+        let url = res.url().clone();
+        let headers = res.headers().clone();
+        let body = res.bytes().await?;
+
+        cache.store(&key, &url, &headers, &body).await;
+        let res = cache::response(Some(url), headers, body);
+
+        Ok(res)
+      },
+      _ => Ok(res),
+    }
+  }
+
+  async fn execute(&self, request: Request) -> Result<Response, reqwest::Error> {
+    let host = request.url().host_str().unwrap_or_default().to_string();
+    let mut attempt = 0;
+
+    loop {
+      self.limiter.acquire(&host).await;
+
+      let Some(next) = request.try_clone() else {
+        return self.inner.execute(request).await;
+      };
+
+      let delay = match self.inner.execute(next).await {
+        Ok(res) if retry::should_retry(res.status()) && attempt < self.config.retries => {
+          retry::retry_after(&res).unwrap_or_else(|| self.backoff(attempt))
+        },
+        Err(e) if retry::is_transient(&e) && attempt < self.config.retries => self.backoff(attempt),
+        res => return res,
+      };
+
+      tokio::time::sleep(delay).await;
+      attempt += 1;
+    }
+  }
+
+  fn backoff(&self, attempt: u32) -> Duration {
+    retry::backoff(self.config.backoff, self.config.max_backoff, attempt)
+  }
+}
@@ -0,0 +1,76 @@
+use std::{collections::HashMap, time::Duration};
+
+use tokio::{sync::Mutex, time::Instant};
+
+#[derive(Debug)]
+struct Bucket {
+  tokens: f64,
+  last: Instant,
+}
+
+/// A token bucket per host. Tokens may go negative, which reserves a slot for
+/// callers that are already waiting so they are served in order.
+#[derive(Debug)]
+pub struct RateLimiter {
+  rate: f64,
+  burst: f64,
+  buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+  pub fn new(requests_per_second: f64, burst: u32) -> Self {
+    Self {
+      rate: requests_per_second,
+      burst: f64::from(burst.max(1)),
+      buckets: Mutex::new(HashMap::new()),
+    }
+  }
+
+  pub async fn acquire(&self, host: &str) {
+    if self.rate <= 0.0 || !self.rate.is_finite() {
+      return;
+    }
+
+    let wait = {
+      let mut buckets = self.buckets.lock().await;
+      let now = Instant::now();
+
+      let bucket = buckets.entry(host.to_string()).or_insert(Bucket {
+        tokens: self.burst,
+        last: now,
+      });
+
+      let elapsed = now.duration_since(bucket.last).as_secs_f64();
+      bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+      bucket.last = now;
+      bucket.tokens -= 1.0;
+
+      if bucket.tokens >= 0.0 {
+        None
+      } else {
+        Some(Duration::from_secs_f64(-bucket.tokens / self.rate))
+      }
+    };
+
+    if let Some(wait) = wait {
+      tokio::time::sleep(wait).await;
+    }
+  }
+}
+
+#[tokio::test(start_paused = true)]
+async fn limits_per_host() {
+  let limiter = RateLimiter::new(2.0, 2);
+  let start = Instant::now();
+
+  for _ in 0..6 {
+    limiter.acquire("apps.rhs.org.uk").await;
+  }
+
+  // two burst tokens, then four more at two per second
+  assert_eq!(start.elapsed(), Duration::from_secs(2));
+
+  let start = Instant::now();
+  limiter.acquire("www.begonias.org").await;
+  assert_eq!(start.elapsed(), Duration::ZERO);
+}
@@ -0,0 +1,66 @@
+use std::time::{Duration, SystemTime};
+
+use reqwest::{header::RETRY_AFTER, Response, StatusCode};
+
+pub(crate) fn should_retry(status: StatusCode) -> bool {
+  status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Whether a request that failed with `error` is worth trying again later, including errors
+/// made from a retryable status by `error_for_status`.
+pub fn is_transient(error: &reqwest::Error) -> bool {
+  error.is_timeout()
+    || error.is_connect()
+    || error.is_request()
+    || error.is_body()
+    || error.status().is_some_and(should_retry)
+}
+
+/// Exponential backoff with full jitter, capped at `max`.
+pub(crate) fn backoff(base: Duration, max: Duration, attempt: u32) -> Duration {
+  let ceiling = base.saturating_mul(2u32.saturating_pow(attempt)).min(max);
+
+  ceiling.mul_f64(fastrand::f64())
+}
+
+pub(crate) fn retry_after(response: &Response) -> Option<Duration> {
+  let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+
+  parse_retry_after(value, SystemTime::now())
+}
+
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+  let value = value.trim();
+
+  if let Ok(seconds) = value.parse::<u64>() {
+    return Some(Duration::from_secs(seconds));
+  }
+
+  let date = httpdate::parse_http_date(value).ok()?;
+
+  Some(date.duration_since(now).unwrap_or_default())
+}
+
+#[test]
+fn retry_after_formats() {
+  let now = httpdate::parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+
+  assert_eq!(
+    parse_retry_after("120", now),
+    Some(Duration::from_secs(120))
+  );
+  assert_eq!(
+    parse_retry_after("Wed, 21 Oct 2015 07:29:00 GMT", now),
+    Some(Duration::from_secs(60))
+  );
+  assert_eq!(
+    parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now),
+    Some(Duration::ZERO)
+  );
+  assert_eq!(parse_retry_after("soon", now), None);
+
+  for attempt in 0..10 {
+    let delay = backoff(Duration::from_millis(500), Duration::from_secs(30), attempt);
+    assert!(delay <= Duration::from_secs(30));
+  }
+}
@@ -0,0 +1,356 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+  extract::{Path, Query, State},
+  http::StatusCode,
+  response::{IntoResponse, Response},
+  routing::get,
+  Json,
+  Router,
+};
+use rhs_international_orchid_register::{
+  api::{search, Details, Get, OnOff, ParentageSearch, SearchResult},
+  csv::{Dump, Pedigree, ProgenyIndex},
+  sql,
+};
+use sqlx::PgPool;
+
+/// Pedigrees and progeny go no further back or forward than this unless asked to.
+const DEFAULT_GENERATIONS: u32 = 3;
+/// Pedigrees double in size with each generation, and progeny of popular parents can run to
+/// tens of thousands of registrations, so requests for more are refused.
+const MAX_GENERATIONS: u32 = 8;
+
+/// Where registrations are served from. Only the orchid register can be mirrored so far.
+#[derive(Clone)]
+pub enum Backend {
+  Dump(Arc<Mirror>),
+  Postgres(PgPool),
+}
+
+impl Backend {
+  pub fn dump(dump: Dump) -> Self {
+    Self::Dump(Arc::new(Mirror::new(Arc::new(dump))))
+  }
+}
+
+/// A dump with the lookups the pedigree and progeny endpoints need, built once up front.
+pub struct Mirror {
+  dump: Arc<Dump>,
+  /// Ids by genus and then epithet.
+  names: HashMap<String, HashMap<String, u32>>,
+  progeny: ProgenyIndex<Arc<Dump>>,
+}
+
+impl Mirror {
+  fn new(dump: Arc<Dump>) -> Self {
+    let mut names: HashMap<String, HashMap<String, u32>> = HashMap::new();
+    for details in dump.iter() {
+      names
+        .entry(details.genus.clone())
+        .or_default()
+        .insert(details.epithet.clone(), details.id);
+    }
+
+    Self {
+      progeny: ProgenyIndex::new(dump.clone()),
+      dump,
+      names,
+    }
+  }
+
+  fn named(&self, genus: &str, epithet: &str) -> Option<&Details> {
+    let id = self.names.get(genus)?.get(epithet)?;
+
+    self.dump.get(Get { id: *id })
+  }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+  #[error("register {0:?} isn't served")]
+  UnknownRegister(String),
+  #[error("at most {MAX_GENERATIONS} generations can be requested")]
+  TooManyGenerations,
+  #[error("not found")]
+  NotFound,
+  #[error(transparent)]
+  Sqlx(#[from] sqlx::Error),
+}
+
+impl IntoResponse for Error {
+  fn into_response(self) -> Response {
+    let status = match self {
+      Error::UnknownRegister(_) | Error::NotFound => StatusCode::NOT_FOUND,
+      Error::TooManyGenerations => StatusCode::BAD_REQUEST,
+      Error::Sqlx(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    (
+      status,
+      Json(serde_json::json!({ "error": self.to_string() })),
+    )
+      .into_response()
+  }
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct NameQuery {
+  genus: Option<String>,
+  grex: Option<String>,
+  #[serde(default)]
+  exact: bool,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct ParentageQuery {
+  seed_genus: Option<String>,
+  seed_grex: Option<String>,
+  pollen_genus: Option<String>,
+  pollen_grex: Option<String>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct GenerationsQuery {
+  generations: Option<u32>,
+}
+
+impl GenerationsQuery {
+  fn generations(&self) -> Result<u32, Error> {
+    match self.generations {
+      Some(generations) if generations > MAX_GENERATIONS => Err(Error::TooManyGenerations),
+      generations => Ok(generations.unwrap_or(DEFAULT_GENERATIONS)),
+    }
+  }
+}
+
+/// `GET /{register}/{id}`, `/{register}/{id}/pedigree`, `/{register}/{id}/progeny`,
+/// `/{register}/search` and `/{register}/parentage`, all returning JSON.
+pub fn router(backend: Backend) -> Router {
+  Router::new()
+    .route("/{register}/search", get(name_search))
+    .route("/{register}/parentage", get(parentage_search))
+    .route("/{register}/{id}", get(details))
+    .route("/{register}/{id}/pedigree", get(pedigree))
+    .route("/{register}/{id}/progeny", get(progeny))
+    .with_state(backend)
+}
+
+fn register(register: &str) -> Result<(), Error> {
+  match register {
+    "orchid" => Ok(()),
+    _ => Err(Error::UnknownRegister(register.to_string())),
+  }
+}
+
+async fn details(
+  State(backend): State<Backend>,
+  Path((register_name, id)): Path<(String, u32)>,
+) -> Result<Json<Details>, Error> {
+  register(&register_name)?;
+
+  let details = match &backend {
+    Backend::Dump(mirror) => mirror.dump.get(Get { id }).cloned(),
+    Backend::Postgres(pool) => sql::registrations::get(Get { id }, pool).await?,
+  };
+
+  details.map(Json).ok_or(Error::NotFound)
+}
+
+async fn name_search(
+  State(backend): State<Backend>,
+  Path(register_name): Path<String>,
+  Query(query): Query<NameQuery>,
+) -> Result<Json<Vec<SearchResult>>, Error> {
+  register(&register_name)?;
+
+  let search = search::Search {
+    genus: query.genus,
+    grex: query.grex,
+    exact: query.exact.then_some(OnOff::On),
+  };
+
+  let results = match &backend {
+    Backend::Dump(mirror) => mirror.dump.name_search(&search),
+    Backend::Postgres(pool) => sql::registrations::name_search(&search, pool).await?,
+  };
+
+  Ok(Json(results))
+}
+
+async fn parentage_search(
+  State(backend): State<Backend>,
+  Path(register_name): Path<String>,
+  Query(query): Query<ParentageQuery>,
+) -> Result<Json<Vec<SearchResult>>, Error> {
+  register(&register_name)?;
+
+  let search = ParentageSearch {
+    seed_genus: query.seed_genus,
+    seed_grex: query.seed_grex,
+    pollen_genus: query.pollen_genus,
+    pollen_grex: query.pollen_grex,
+  };
+
+  let results = match &backend {
+    Backend::Dump(mirror) => mirror.dump.parentage_search(&search),
+    Backend::Postgres(pool) => sql::registrations::parentage_search(&search, pool).await?,
+  };
+
+  Ok(Json(results))
+}
+
+async fn pedigree(
+  State(backend): State<Backend>,
+  Path((register_name, id)): Path<(String, u32)>,
+  Query(query): Query<GenerationsQuery>,
+) -> Result<Json<Pedigree>, Error> {
+  register(&register_name)?;
+  let generations = query.generations()?;
+
+  let pedigree = match &backend {
+    Backend::Dump(mirror) => mirror.dump.get(Get { id }).map(|root| {
+      Pedigree::build(root, Some(generations), &|parent| {
+        mirror.named(&parent.genus, &parent.epithet)
+      })
+    }),
+    Backend::Postgres(pool) => {
+      sql::registrations::pedigree(Get { id }, Some(generations), pool).await?
+    },
+  };
+
+  pedigree.map(Json).ok_or(Error::NotFound)
+}
+
+async fn progeny(
+  State(backend): State<Backend>,
+  Path((register_name, id)): Path<(String, u32)>,
+  Query(query): Query<GenerationsQuery>,
+) -> Result<Response, Error> {
+  register(&register_name)?;
+  let generations = query.generations()?;
+
+  match &backend {
+    Backend::Dump(mirror) => {
+      let details = mirror.dump.get(Get { id }).ok_or(Error::NotFound)?;
+
+      let mut progeny = mirror
+        .progeny
+        .progeny(&details.genus, &details.epithet, Some(generations));
+      progeny
+        .descendants
+        .sort_by_key(|d| (d.generation, d.details.id));
+
+      Ok(Json(progeny.descendants).into_response())
+    },
+    Backend::Postgres(pool) => {
+      if sql::registrations::get(Get { id }, pool).await?.is_none() {
+        return Err(Error::NotFound);
+      }
+
+      let descendants = sql::registrations::progeny(Get { id }, Some(generations), pool).await?;
+
+      Ok(Json(descendants).into_response())
+    },
+  }
+}
+
+#[cfg(test)]
+async fn request(router: &Router, uri: &str) -> (StatusCode, serde_json::Value) {
+  use http_body_util::BodyExt;
+  use tower::ServiceExt;
+
+  let res = router
+    .clone()
+    .oneshot(
+      axum::http::Request::get(uri)
+        .body(axum::body::Body::empty())
+        .unwrap(),
+    )
+    .await
+    .unwrap();
+
+  let status = res.status();
+  let body = res.into_body().collect().await.unwrap().to_bytes();
+
+  (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn dump_backend() {
+  use rhs_international_orchid_register::api::Parent;
+
+  let grex = |id: u32, name: &str, seed: Option<&str>, pollen: Option<&str>| {
+    let parent = |epithet: &str| Parent {
+      genus: "Phalaenopsis".into(),
+      epithet: epithet.into(),
+    };
+
+    Details {
+      id,
+      genus: "Phalaenopsis".into(),
+      epithet: name.into(),
+      synonym: false,
+      synonym_details: None,
+      registrant_name: None,
+      originator_name: None,
+      date_of_registration: Some(
+        time::Date::from_calendar_date(1927, time::Month::January, 1).unwrap(),
+      ),
+      seed_parent: seed.map(parent),
+      pollen_parent: pollen.map(parent),
+    }
+  };
+
+  let mut dump = Dump::empty();
+  dump.insert(grex(1, "amabilis", None, None));
+  dump.insert(grex(2, "schilleriana", None, None));
+  dump.insert(grex(
+    3,
+    "Elisabethae",
+    Some("amabilis"),
+    Some("schilleriana"),
+  ));
+  dump.insert(grex(4, "Doris", Some("Elisabethae"), Some("Elisabethae")));
+
+  let router = router(Backend::dump(dump));
+
+  let (status, body) = request(&router, "/orchid/3").await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body["epithet"], "Elisabethae");
+  assert_eq!(body["date_of_registration"], "1927-01-01");
+  assert_eq!(body["seed_parent"]["epithet"], "amabilis");
+
+  let (status, body) = request(&router, "/orchid/search?grex=s&exact=false").await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(
+    body,
+    serde_json::json!([{ "genus": "Phalaenopsis", "grex": "schilleriana", "id": 2 }])
+  );
+
+  let (_, body) = request(&router, "/orchid/parentage?seed_grex=amabilis").await;
+  assert_eq!(body[0]["id"], 3);
+
+  let (_, body) = request(&router, "/orchid/4/pedigree").await;
+  assert_eq!(body["seed_parent"]["pollen_parent"]["details"]["id"], 2);
+
+  let (_, body) = request(&router, "/orchid/1/progeny?generations=1").await;
+  assert_eq!(body.as_array().unwrap().len(), 1);
+  assert_eq!(body[0]["generation"], 1);
+  assert_eq!(body[0]["details"]["id"], 3);
+
+  let (_, body) = request(&router, "/orchid/4/pedigree?generations=1").await;
+  assert_eq!(body["seed_parent"]["details"]["id"], 3);
+  assert!(body["seed_parent"]["seed_parent"].is_null());
+
+  let (status, body) = request(&router, "/orchid/1/progeny?generations=9").await;
+  assert_eq!(status, StatusCode::BAD_REQUEST);
+  assert_eq!(body["error"], "at most 8 generations can be requested");
+
+  let (status, _) = request(&router, "/orchid/5").await;
+  assert_eq!(status, StatusCode::NOT_FOUND);
+
+  let (status, body) = request(&router, "/daffodil/1").await;
+  assert_eq!(status, StatusCode::NOT_FOUND);
+  assert_eq!(body["error"], "register \"daffodil\" isn't served");
+}
@@ -0,0 +1,84 @@
+use std::{error::Error, net::SocketAddr, path::PathBuf};
+
+use clap::Parser;
+use icra_server::Backend;
+use rhs_international_orchid_register::{
+  csv::{Dump, Patches},
+  sql,
+};
+
+/// Serve a mirrored orchid register as JSON, from either `dump.csv` or Postgres.
+#[derive(Debug, Parser)]
+#[command(name = "icra-server", version)]
+struct Args {
+  #[arg(long, default_value = "127.0.0.1:3000")]
+  addr: SocketAddr,
+  /// Directory containing `dump.csv`. Takes precedence over `--database-url`, which may be
+  /// set from the environment.
+  #[arg(long, required_unless_present = "database_url")]
+  data: Option<PathBuf>,
+  #[arg(long, requires = "data")]
+  patches: Option<PathBuf>,
+  #[arg(long, env = "DATABASE_URL")]
+  database_url: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+  let args = Args::parse();
+
+  let backend = match (args.data, args.database_url) {
+    (Some(data), _) => {
+      let mut dump = Dump::from_data_file(data.join("dump.csv")).await?;
+
+      if let Some(patches) = args.patches {
+        let patches = Patches::from_data_file(patches).await?;
+
+        for outcome in patches.apply_all(dump.data_mut()).needs_review() {
+          eprintln!(
+            "patch for {} needs review: {:?}",
+            outcome.patch.id, outcome.status
+          );
+        }
+      }
+
+      Backend::dump(dump)
+    },
+    (None, Some(database_url)) => {
+      let pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await?;
+
+      sql::migrate(&pool).await?;
+
+      Backend::Postgres(pool)
+    },
+    (None, None) => unreachable!("clap requires one of --data or --database-url"),
+  };
+
+  let listener = tokio::net::TcpListener::bind(args.addr).await?;
+  eprintln!("listening on {}", listener.local_addr()?);
+
+  axum::serve(listener, icra_server::router(backend)).await?;
+
+  Ok(())
+}
+
+#[test]
+fn args() {
+  use clap::CommandFactory;
+
+  Args::command().debug_assert();
+
+  assert!(Args::try_parse_from(["icra-server"]).is_err());
+  assert!(Args::try_parse_from(["icra-server", "--data", "data"]).is_ok());
+  assert!(Args::try_parse_from([
+    "icra-server",
+    "--database-url",
+    "postgres://localhost/icra",
+    "--data",
+    "data"
+  ])
+  .is_ok());
+}
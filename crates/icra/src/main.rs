@@ -0,0 +1,374 @@
+use std::{
+  error::Error,
+  path::{Path, PathBuf},
+  process::ExitCode,
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use rhs_international_daffodil_register as daffodil;
+use rhs_international_orchid_register as orchid;
+
+mod output;
+
+#[derive(Debug, Parser)]
+#[command(
+  name = "icra",
+  version,
+  about = "Query and mirror international cultivar registers"
+)]
+struct Cli {
+  /// Print JSON instead of tables.
+  #[arg(long, global = true)]
+  json: bool,
+  /// Keep HTTP responses in this directory and revalidate them instead of refetching.
+  #[arg(long, global = true, value_name = "DIR")]
+  cache: Option<PathBuf>,
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Register {
+  Orchid,
+  Daffodil,
+  Begonia,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+  Jsonl,
+  Parquet,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+  /// Fetch a single registration by its register id.
+  Lookup { register: Register, id: u32 },
+  /// Search a register by name.
+  Search {
+    register: Register,
+    #[arg(long)]
+    genus: Option<String>,
+    #[arg(long)]
+    grex: Option<String>,
+    /// Only match the grex exactly, rather than as a prefix.
+    #[arg(long)]
+    exact: bool,
+  },
+  /// Search a register by the names of a registration's parents.
+  Parentage {
+    register: Register,
+    #[arg(long)]
+    seed_genus: Option<String>,
+    #[arg(long)]
+    seed_grex: Option<String>,
+    #[arg(long)]
+    pollen_genus: Option<String>,
+    #[arg(long)]
+    pollen_grex: Option<String>,
+  },
+  /// Crawl a register into `dump.csv` and `known_bad.csv`, resuming from what's already there.
+  /// Progress is kept in `dump.log` until it's written back to the CSV files.
+  Mirror {
+    register: Register,
+    #[arg(long, default_value = "data")]
+    data: PathBuf,
+    #[arg(long)]
+    start: Option<u32>,
+    #[arg(long)]
+    end: Option<u32>,
+    #[arg(long)]
+    concurrency: Option<usize>,
+    /// Only look for registrations made since the last mirror.
+    #[arg(long)]
+    new: bool,
+  },
+  /// Load a mirrored orchid register into Postgres.
+  ImportPostgres {
+    #[arg(long, default_value = "data")]
+    data: PathBuf,
+    #[arg(long)]
+    patches: Option<PathBuf>,
+    #[arg(long, env = "DATABASE_URL")]
+    database_url: String,
+  },
+  /// Load a mirrored orchid register into a single SQLite file, creating it if needed.
+  ImportSqlite {
+    #[arg(long, default_value = "data")]
+    data: PathBuf,
+    #[arg(long)]
+    patches: Option<PathBuf>,
+    output: PathBuf,
+  },
+  /// Write a mirrored orchid register as JSON Lines or Parquet.
+  Export {
+    #[arg(long, default_value = "data")]
+    data: PathBuf,
+    #[arg(long)]
+    patches: Option<PathBuf>,
+    #[arg(long, value_enum)]
+    format: Format,
+    output: PathBuf,
+  },
+  /// Check a mirrored orchid register for broken data, failing if there are any issues.
+  Validate {
+    #[arg(long, default_value = "data")]
+    data: PathBuf,
+    #[arg(long)]
+    patches: Option<PathBuf>,
+  },
+}
+
+#[tokio::main]
+async fn main() -> Result<ExitCode, Box<dyn Error>> {
+  let cli = Cli::parse();
+
+  let client = icra_http::Client::new(icra_http::Config {
+    cache: cli.cache.clone().map(icra_http::CacheConfig::new),
+    ..Default::default()
+  })?;
+  let json = cli.json;
+
+  match cli.command {
+    Command::Lookup { register, id } => match register {
+      Register::Orchid => {
+        let details = orchid::api::Get { id }.lookup_with(&client).await?;
+        output::print(&details, json)?;
+      },
+      Register::Daffodil => {
+        let details = daffodil::api::Get { id }.lookup_with(&client).await?;
+        output::print(&details, json)?;
+      },
+      Register::Begonia => {
+        let details = american_begonia_society::Get { id }
+          .lookup_with(&client)
+          .await?;
+        output::print(&details, json)?;
+      },
+    },
+    Command::Search {
+      register,
+      genus,
+      grex,
+      exact,
+    } => {
+      let results = match register {
+        Register::Orchid => {
+          orchid::api::search::Search {
+            genus,
+            grex,
+            exact: exact.then_some(orchid::api::OnOff::On),
+          }
+          .search_with(&client)
+          .await?
+        },
+        // the daffodil crate's search forms are copies of the orchid register's and the begonia
+        // society has no search at all, so neither is offered
+        Register::Daffodil | Register::Begonia => return unsupported("search", register),
+      };
+
+      output::print(&results, json)?;
+    },
+    Command::Parentage {
+      register,
+      seed_genus,
+      seed_grex,
+      pollen_genus,
+      pollen_grex,
+    } => match register {
+      Register::Orchid => {
+        let results = orchid::api::ParentageSearch {
+          seed_genus,
+          seed_grex,
+          pollen_genus,
+          pollen_grex,
+        }
+        .search_with(&client)
+        .await?;
+
+        output::print(&results, json)?;
+      },
+      Register::Daffodil | Register::Begonia => return unsupported("parentage", register),
+    },
+    Command::Mirror {
+      register,
+      data,
+      start,
+      end,
+      concurrency,
+      new,
+    } => {
+      if register != Register::Orchid {
+        return unsupported("mirror", register);
+      }
+
+      tokio::fs::create_dir_all(&data).await?;
+
+      let defaults = orchid::crawler::Config::default();
+      let config = orchid::crawler::Config {
+        start: start.unwrap_or(defaults.start),
+        end: end.unwrap_or(defaults.end),
+        concurrency: concurrency.unwrap_or(defaults.concurrency),
+        data: data.join("dump.csv"),
+        known_bad: data.join("known_bad.csv"),
+        log: Some(data.join("dump.log")),
+        ..defaults
+      };
+
+      let mut crawler = orchid::crawler::Crawler::from_files(config)
+        .await?
+        .with_client(client);
+
+      if new {
+        let details = crawler.new_registrations().await?;
+        output::print(&details, json)?;
+      } else {
+        let summary = crawler
+          .run_until(async {
+            tokio::signal::ctrl_c().await.ok();
+          })
+          .await?;
+
+        output::print(
+          &serde_json::json!({
+            "fetched": summary.fetched.len(),
+            "not_found": summary.not_found.len(),
+            "transient": summary.transient.len(),
+            "errored": summary.errored,
+            "interrupted": summary.interrupted,
+          }),
+          json,
+        )?;
+      }
+    },
+    Command::ImportPostgres {
+      data,
+      patches,
+      database_url,
+    } => {
+      let patches = patches_from(patches.as_deref()).await?;
+      let dump = load(&data, patches.as_ref()).await?;
+
+      let pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await?;
+
+      orchid::sql::migrate(&pool).await?;
+
+      let import = orchid::sql::import(dump.data().values().cloned(), &pool, |done, len| {
+        eprintln!("{done}/{len}");
+      })
+      .await?;
+
+      output::print(&import, json)?;
+    },
+    Command::ImportSqlite {
+      data,
+      patches,
+      output,
+    } => {
+      let patches = patches_from(patches.as_deref()).await?;
+      let dump = load(&data, patches.as_ref()).await?;
+
+      let pool = orchid::sqlite::connect(output).await?;
+      orchid::sqlite::migrate(&pool).await?;
+
+      let import = orchid::sqlite::import(dump.data().values().cloned(), &pool, |done, len| {
+        eprintln!("{done}/{len}");
+      })
+      .await?;
+
+      output::print(&import, json)?;
+    },
+    Command::Export {
+      data,
+      patches,
+      format,
+      output,
+    } => {
+      let patches = patches_from(patches.as_deref()).await?;
+      let dump = load(&data, patches.as_ref()).await?;
+      let writer = std::io::BufWriter::new(std::fs::File::create(output)?);
+
+      match format {
+        Format::Jsonl => dump.write_jsonl(writer)?,
+        Format::Parquet => dump.write_parquet(writer)?,
+      }
+    },
+    Command::Validate { data, patches } => {
+      let patches = patches_from(patches.as_deref()).await?;
+      let dump = load(&data, patches.as_ref()).await?;
+
+      let mut validation = dump.validate();
+      if let Some(patches) = &patches {
+        validation.suppress(patches);
+      }
+
+      output::print(&validation.issues, json)?;
+
+      if !validation.is_empty() {
+        return Ok(ExitCode::FAILURE);
+      }
+    },
+  }
+
+  Ok(ExitCode::SUCCESS)
+}
+
+async fn patches_from(path: Option<&Path>) -> Result<Option<orchid::csv::Patches>, Box<dyn Error>> {
+  match path {
+    Some(path) => Ok(Some(orchid::csv::Patches::from_data_file(path).await?)),
+    None => Ok(None),
+  }
+}
+
+/// Loads an orchid mirror from `data`, applying `patches` if given.
+async fn load(
+  data: &Path,
+  patches: Option<&orchid::csv::Patches>,
+) -> Result<orchid::csv::Dump, Box<dyn Error>> {
+  let mut dump = orchid::csv::Dump::from_data_file(data.join("dump.csv")).await?;
+
+  if let Some(patches) = patches {
+    for outcome in patches.apply_all(dump.data_mut()).needs_review() {
+      eprintln!(
+        "patch for {} needs review: {:?}",
+        outcome.patch.id, outcome.status
+      );
+    }
+  }
+
+  Ok(dump)
+}
+
+fn unsupported(command: &str, register: Register) -> Result<ExitCode, Box<dyn Error>> {
+  let register = register
+    .to_possible_value()
+    .map(|value| value.get_name().to_string())
+    .unwrap_or_default();
+
+  eprintln!("{command} isn't supported for the {register} register");
+
+  Ok(ExitCode::FAILURE)
+}
+
+#[test]
+fn cli() {
+  use clap::CommandFactory;
+
+  Cli::command().debug_assert();
+
+  let cli =
+    Cli::try_parse_from(["icra", "search", "orchid", "--genus", "Cattleya", "--json"]).unwrap();
+  assert!(cli.json);
+  assert!(matches!(
+    cli.command,
+    Command::Search {
+      register: Register::Orchid,
+      genus: Some(_),
+      grex: None,
+      exact: false,
+    }
+  ));
+}
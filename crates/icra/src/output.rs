@@ -0,0 +1,151 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// Prints `value` as pretty JSON, or as a table: one row per element for sequences, and one
+/// per field for anything else. Nested fields are flattened into `parent.child` columns.
+pub fn print(value: &impl Serialize, json: bool) -> Result<(), serde_json::Error> {
+  let value = serde_json::to_value(value)?;
+
+  if json {
+    println!("{}", serde_json::to_string_pretty(&value)?);
+  } else {
+    print!("{}", render(&value));
+  }
+
+  Ok(())
+}
+
+fn render(value: &Value) -> String {
+  match value {
+    Value::Array(rows) if rows.is_empty() => "no results\n".to_string(),
+    Value::Array(rows) => table(rows),
+    Value::Object(_) => {
+      let mut fields = Vec::new();
+      flatten(None, value, &mut fields);
+
+      let width = fields.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+
+      fields
+        .iter()
+        .map(|(key, value)| format!("{}\n", format!("{key:width$}  {value}").trim_end()))
+        .collect()
+    },
+    other => format!("{}\n", cell(other)),
+  }
+}
+
+fn table(rows: &[Value]) -> String {
+  let rows = rows
+    .iter()
+    .map(|row| {
+      let mut fields = Vec::new();
+      flatten(None, row, &mut fields);
+      fields
+    })
+    .collect::<Vec<_>>();
+
+  let mut columns = Vec::<String>::new();
+  for (key, _) in rows.iter().flatten() {
+    if !columns.contains(key) {
+      columns.push(key.clone());
+    }
+  }
+
+  let cells = rows
+    .iter()
+    .map(|row| {
+      columns
+        .iter()
+        .map(|column| {
+          row
+            .iter()
+            .find(|(key, _)| key == column)
+            .map(|(_, value)| value.as_str())
+            .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+    })
+    .collect::<Vec<_>>();
+
+  let widths = columns
+    .iter()
+    .enumerate()
+    .map(|(i, column)| {
+      cells
+        .iter()
+        .map(|row| row[i].chars().count())
+        .chain([column.len()])
+        .max()
+        .unwrap_or(0)
+    })
+    .collect::<Vec<_>>();
+
+  let line = |cells: Vec<String>| {
+    let line = cells
+      .iter()
+      .zip(&widths)
+      .map(|(cell, width)| format!("{cell:width$}"))
+      .collect::<Vec<_>>()
+      .join("  ");
+
+    format!("{}\n", line.trim_end())
+  };
+
+  let mut out = line(columns.clone());
+  out += &line(widths.iter().map(|width| "-".repeat(*width)).collect());
+
+  for row in cells {
+    out += &line(row.into_iter().map(str::to_string).collect());
+  }
+
+  out
+}
+
+fn flatten(prefix: Option<&str>, value: &Value, out: &mut Vec<(String, String)>) {
+  match value {
+    Value::Object(fields) => {
+      for (key, value) in fields {
+        let key = match prefix {
+          Some(prefix) => format!("{prefix}.{key}"),
+          None => key.clone(),
+        };
+
+        flatten(Some(&key), value, out);
+      }
+    },
+    value => out.push((prefix.unwrap_or("value").to_string(), cell(value))),
+  }
+}
+
+fn cell(value: &Value) -> String {
+  match value {
+    Value::Null => String::new(),
+    Value::String(s) => s.clone(),
+    Value::Array(values) => values.iter().map(cell).collect::<Vec<_>>().join(", "),
+    other => other.to_string(),
+  }
+}
+
+#[test]
+fn tables() {
+  let value = serde_json::json!([
+    { "genus": "Phalaenopsis", "grex": "Doris", "id": 1437 },
+    { "genus": "Cattleya", "grex": "Bow Bells", "id": 2, "parent": { "genus": "Cattleya" } },
+  ]);
+
+  assert_eq!(
+    render(&value),
+    "\
+genus         grex       id    parent.genus
+------------  ---------  ----  ------------
+Phalaenopsis  Doris      1437
+Cattleya      Bow Bells  2     Cattleya
+"
+  );
+
+  let value = serde_json::json!({ "id": 1437, "seed_parent": null, "ty": ["rhizome", "superba"] });
+  assert_eq!(
+    render(&value),
+    "id           1437\nseed_parent\nty           rhizome, superba\n"
+  );
+}
@@ -1,30 +1,38 @@
+#[cfg(feature = "scrape")]
 use scraper::{Html, Selector};
-use time::{macros::format_description, Date};
+#[cfg(feature = "scrape")]
+use time::macros::format_description;
+use time::Date;
 
-#[derive(Clone, Copy, Debug, Default, serde::Serialize)]
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Get {
   pub id: u32,
 }
 
-#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq, Hash, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Parent {
   pub genus: String,
   pub epithet: String,
 }
 
-#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq, Hash, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Synonym {
   pub genus: String,
   pub epithet: Option<String>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FirstFlowering {
   Pre(u32),
   Year(u32),
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Details {
   pub id: u32,
   pub name: String,
@@ -38,6 +46,7 @@ pub struct Details {
   pub last_modified: Option<Date>,
 }
 
+#[cfg(feature = "scrape")]
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
   #[error("Not found")]
@@ -48,14 +57,19 @@ pub enum Error {
   Time(#[from] time::error::Parse),
 }
 
+#[cfg(feature = "scrape")]
 impl Get {
   pub async fn lookup(&self) -> Result<Details, Error> {
-    let client = reqwest::Client::new();
+    self.lookup_with(icra_http::Client::shared()).await
+  }
 
+  pub async fn lookup_with(&self, client: &icra_http::Client) -> Result<Details, Error> {
     let res = client
-      .get("https://apps.rhs.org.uk/horticulturaldatabase/daffodilregister/daffdetails.asp")
-      .query(&[("ID", self.id)])
-      .send()
+      .send(
+        client
+          .get("https://apps.rhs.org.uk/horticulturaldatabase/daffodilregister/daffdetails.asp")
+          .query(&[("ID", self.id)]),
+      )
       .await?;
 
     let html = res.text().await?;
@@ -66,16 +80,25 @@ impl Get {
   }
 }
 
+#[cfg(feature = "blocking")]
+impl Get {
+  pub fn lookup_blocking(&self) -> Result<Details, Error> {
+    icra_http::block_on(self.lookup())
+  }
+
+  pub fn lookup_blocking_with(&self, client: &icra_http::Client) -> Result<Details, Error> {
+    icra_http::block_on(self.lookup_with(client))
+  }
+}
+
+#[cfg(feature = "scrape")]
 fn to_string(el: scraper::ElementRef<'_>) -> String {
   let text = el.text().map(|t| t.to_string()).collect::<String>();
 
   text.trim().to_string()
 }
 
-fn name_exists(name: &str) -> bool {
-  !matches!(name, "O/U" | "Unknown" | "")
-}
-
+#[cfg(feature = "scrape")]
 fn parse(id: u32, html: Html) -> Result<Details, Error> {
   let name = Selector::parse("h2.specimen").unwrap();
   let table = Selector::parse("table.results").unwrap();
@@ -155,9 +178,9 @@ fn parse(id: u32, html: Html) -> Result<Details, Error> {
   Ok(details)
 }
 
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn get() -> Result<(), Box<dyn std::error::Error>> {
-  let start = 200_001;
   let start = 200067;
   for i in start..start + 100 {
     let get: Get = Get { id: i };
@@ -169,6 +192,7 @@ async fn get() -> Result<(), Box<dyn std::error::Error>> {
   Ok(())
 }
 
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn species() -> Result<(), Box<dyn std::error::Error>> {
   let get: Get = Get { id: 1437 };
@@ -179,6 +203,7 @@ async fn species() -> Result<(), Box<dyn std::error::Error>> {
   Ok(())
 }
 
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn species_synonym() -> Result<(), Box<dyn std::error::Error>> {
   let get: Get = Get { id: 1475 };
@@ -189,6 +214,7 @@ async fn species_synonym() -> Result<(), Box<dyn std::error::Error>> {
   Ok(())
 }
 
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn hybrid() -> Result<(), Box<dyn std::error::Error>> {
   let get = Get { id: 1064207 };
@@ -199,6 +225,7 @@ async fn hybrid() -> Result<(), Box<dyn std::error::Error>> {
   Ok(())
 }
 
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn hybrid_synonym() -> Result<(), Box<dyn std::error::Error>> {
   let get = Get { id: 134249 };
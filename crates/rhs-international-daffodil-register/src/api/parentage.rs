@@ -1,29 +1,41 @@
+#[cfg(feature = "scrape")]
 use std::str::FromStr;
 
+#[cfg(feature = "scrape")]
 use scraper::{Html, Selector};
 
+#[cfg(feature = "scrape")]
 use crate::api::SearchResult;
 
-#[derive(Debug, Default, serde::Serialize)]
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ParentageSearch {
-  #[serde(rename = "seedgen")]
+  #[cfg_attr(feature = "serde", serde(rename = "seedgen"))]
   pub seed_genus: Option<String>,
-  #[serde(rename = "seedgrex")]
+  #[cfg_attr(feature = "serde", serde(rename = "seedgrex"))]
   pub seed_grex: Option<String>,
-  #[serde(rename = "pollgen")]
+  #[cfg_attr(feature = "serde", serde(rename = "pollgen"))]
   pub pollen_genus: Option<String>,
-  #[serde(rename = "pollgrex")]
+  #[cfg_attr(feature = "serde", serde(rename = "pollgrex"))]
   pub pollen_grex: Option<String>,
 }
 
+#[cfg(feature = "scrape")]
 impl ParentageSearch {
   pub async fn search(&self) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
-    let client = reqwest::Client::new();
+    self.search_with(icra_http::Client::shared()).await
+  }
 
+  pub async fn search_with(
+    &self,
+    client: &icra_http::Client,
+  ) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
     let res = client
-      .post("https://apps.rhs.org.uk/horticulturaldatabase/orchidregister/parentageresults.asp")
-      .form(self)
-      .send()
+      .send(
+        client
+          .post("https://apps.rhs.org.uk/horticulturaldatabase/orchidregister/parentageresults.asp")
+          .form(self),
+      )
       .await?;
 
     let html = res.text().await?;
@@ -33,6 +45,21 @@ impl ParentageSearch {
   }
 }
 
+#[cfg(feature = "blocking")]
+impl ParentageSearch {
+  pub fn search_blocking(&self) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
+    icra_http::block_on(self.search())
+  }
+
+  pub fn search_blocking_with(
+    &self,
+    client: &icra_http::Client,
+  ) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
+    icra_http::block_on(self.search_with(client))
+  }
+}
+
+#[cfg(feature = "scrape")]
 fn parse(html: Html) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
   let table = Selector::parse("table.results")?;
   let table = html
@@ -72,6 +99,7 @@ fn parse(html: Html) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
   Ok(res)
 }
 
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn rhs() -> Result<(), Box<dyn std::error::Error>> {
   let search = ParentageSearch {
@@ -1,37 +1,51 @@
+#[cfg(feature = "scrape")]
 use std::str::FromStr;
 
+#[cfg(feature = "scrape")]
 use scraper::{Html, Selector};
 
+#[cfg(feature = "scrape")]
 use crate::api::SearchResult;
 
-#[derive(Debug, serde::Serialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum OnOff {
   On,
   Off,
 }
 
-#[derive(Debug, Default, serde::Serialize)]
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Search {
   pub genus: Option<String>,
   pub grex: Option<String>,
-  #[serde(rename = "wild", default = "exact_default")]
+  #[cfg_attr(feature = "serde", serde(rename = "wild", default = "exact_default"))]
   pub exact: Option<OnOff>,
 }
 
+#[cfg(feature = "serde")]
 #[allow(dead_code)]
 fn exact_default() -> Option<OnOff> {
   Some(OnOff::Off)
 }
 
+#[cfg(feature = "scrape")]
 impl Search {
   pub async fn search(&self) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
-    let client = reqwest::Client::new();
+    self.search_with(icra_http::Client::shared()).await
+  }
 
+  pub async fn search_with(
+    &self,
+    client: &icra_http::Client,
+  ) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
     let res = client
-      .post("https://apps.rhs.org.uk/horticulturaldatabase/orchidregister/orchidresults.asp")
-      .form(self)
-      .send()
+      .send(
+        client
+          .post("https://apps.rhs.org.uk/horticulturaldatabase/orchidregister/orchidresults.asp")
+          .form(self),
+      )
       .await?;
 
     let html = res.text().await?;
@@ -41,6 +55,21 @@ impl Search {
   }
 }
 
+#[cfg(feature = "blocking")]
+impl Search {
+  pub fn search_blocking(&self) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
+    icra_http::block_on(self.search())
+  }
+
+  pub fn search_blocking_with(
+    &self,
+    client: &icra_http::Client,
+  ) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
+    icra_http::block_on(self.search_with(client))
+  }
+}
+
+#[cfg(feature = "scrape")]
 fn parse(html: Html) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
   let table = Selector::parse("table.results")?;
   let table = html
@@ -80,6 +109,7 @@ fn parse(html: Html) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
   Ok(res)
 }
 
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn rhs() -> Result<(), Box<dyn std::error::Error>> {
   let search = Search {
@@ -0,0 +1,126 @@
+use std::{io::Write, sync::Arc};
+
+use icra_export::{
+  arrow_array::{ArrayRef, BooleanArray, Date32Array, StringArray, UInt32Array},
+  arrow_schema::{DataType, Field},
+  date32,
+  struct_array,
+  Error,
+};
+
+use crate::api::get::{Details, FirstFlowering};
+
+/// Writes each entry as a JSON object on its own line, in the order given.
+pub fn write_jsonl<'a>(
+  details: impl IntoIterator<Item = &'a Details>,
+  writer: impl Write,
+) -> Result<(), Error> {
+  icra_export::write_jsonl(details, writer)
+}
+
+/// Writes entries to Parquet in the order given. The first flowering date is a struct of its
+/// year and whether the register only gives it as "pre" or "c." that year.
+pub fn write_parquet<'a>(
+  details: impl IntoIterator<Item = &'a Details>,
+  writer: impl Write + Send,
+) -> Result<(), Error> {
+  let details = details.into_iter().collect::<Vec<_>>();
+
+  let string = |value: fn(&Details) -> &str| -> ArrayRef {
+    Arc::new(StringArray::from_iter_values(
+      details.iter().map(|d| value(d)),
+    ))
+  };
+
+  let first_flowering_date = struct_array(
+    vec![
+      (
+        Field::new("year", DataType::UInt32, false),
+        Arc::new(UInt32Array::from_iter_values(details.iter().map(
+          |d| match d.first_flowering_date {
+            FirstFlowering::Pre(year) | FirstFlowering::Year(year) => year,
+          },
+        ))),
+      ),
+      (
+        Field::new("pre", DataType::Boolean, false),
+        Arc::new(BooleanArray::from_iter(details.iter().map(|d| {
+          Some(matches!(d.first_flowering_date, FirstFlowering::Pre(_)))
+        }))),
+      ),
+    ],
+    details.iter().map(|_| true),
+  )?;
+
+  icra_export::write_parquet(
+    vec![
+      (
+        Field::new("id", DataType::UInt32, false),
+        Arc::new(UInt32Array::from_iter_values(details.iter().map(|d| d.id))),
+      ),
+      (
+        Field::new("name", DataType::Utf8, false),
+        string(|d| &d.name),
+      ),
+      (
+        Field::new("registered", DataType::Boolean, false),
+        Arc::new(BooleanArray::from_iter(
+          details.iter().map(|d| Some(d.registered)),
+        )),
+      ),
+      (
+        Field::new("division", DataType::Utf8, false),
+        string(|d| &d.division),
+      ),
+      (
+        Field::new("perianth_colors", DataType::Utf8, false),
+        string(|d| &d.perianth_colors),
+      ),
+      (
+        Field::new("originator_name", DataType::Utf8, false),
+        string(|d| &d.originator_name),
+      ),
+      (
+        Field::new(
+          "first_flowering_date",
+          first_flowering_date.data_type().clone(),
+          false,
+        ),
+        first_flowering_date,
+      ),
+      (
+        Field::new("last_modified", DataType::Date32, true),
+        Arc::new(Date32Array::from_iter(
+          details.iter().map(|d| d.last_modified.map(date32)),
+        )),
+      ),
+    ],
+    writer,
+  )
+}
+
+#[test]
+fn export() -> Result<(), Error> {
+  let details = [Details {
+    id: 1437,
+    name: "King Alfred".into(),
+    registered: true,
+    division: "1 Y-Y".into(),
+    perianth_colors: "Y".into(),
+    originator_name: "Kendall".into(),
+    first_flowering_date: FirstFlowering::Pre(1899),
+    last_modified: None,
+  }];
+
+  let mut jsonl = Vec::new();
+  write_jsonl(&details, &mut jsonl)?;
+  assert!(String::from_utf8(jsonl)
+    .unwrap()
+    .contains("\"first_flowering_date\":{\"Pre\":1899}"));
+
+  let mut parquet = Vec::new();
+  write_parquet(&details, &mut parquet)?;
+  assert!(parquet.starts_with(b"PAR1"));
+
+  Ok(())
+}
@@ -1 +1,3 @@
-mod api;
+pub mod api;
+#[cfg(feature = "export")]
+pub mod export;
@@ -0,0 +1,27 @@
+use rhs_international_orchid_register::csv::{Dump, DumpError};
+
+/// `cargo run --example diff -- old.csv new.csv`, writing `diff.csv` and `diff.json`.
+#[tokio::main]
+async fn main() -> Result<(), DumpError> {
+  let mut args = std::env::args().skip(1);
+  let old = args.next().unwrap_or_else(|| "data/dump.csv".into());
+  let new = args.next().unwrap_or_else(|| "dump.csv".into());
+
+  let old = Dump::from_data_file(old).await?;
+  let new = Dump::from_data_file(new).await?;
+  let diff = old.diff(&new);
+
+  println!(
+    "added {}, removed {}, modified {}",
+    diff.added.len(),
+    diff.removed.len(),
+    diff.modified.len()
+  );
+
+  diff.write_csv(std::fs::File::create("diff.csv")?)?;
+  diff
+    .write_json(std::fs::File::create("diff.json")?)
+    .map_err(std::io::Error::from)?;
+
+  Ok(())
+}
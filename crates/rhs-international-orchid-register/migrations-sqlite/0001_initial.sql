@@ -0,0 +1,24 @@
+CREATE TABLE genera (
+  id INTEGER PRIMARY KEY,
+  name TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE details (
+  id INTEGER PRIMARY KEY,
+  registar_id INTEGER NOT NULL,
+  genus INTEGER REFERENCES genera(id) NOT NULL,
+  epithet TEXT NOT NULL,
+  synonym BOOLEAN NOT NULL DEFAULT FALSE,
+  synonym_genus INTEGER REFERENCES genera(id),
+  synonym_epithet INTEGER REFERENCES details(id),
+  registrant_name TEXT,
+  originator_name TEXT,
+  date_of_registration DATE,
+  seed_parent INTEGER REFERENCES details(id),
+  pollen_parent INTEGER REFERENCES details(id),
+  UNIQUE ( genus, epithet )
+);
+
+CREATE INDEX details_registar_id ON details ( registar_id );
+CREATE INDEX details_seed_parent ON details ( seed_parent );
+CREATE INDEX details_pollen_parent ON details ( pollen_parent );
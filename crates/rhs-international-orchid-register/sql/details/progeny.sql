@@ -0,0 +1,28 @@
+WITH RECURSIVE progeny AS (
+  SELECT details.*, 1 AS generation, ARRAY[$1::UUID, details.id] AS path
+  FROM details
+  WHERE seed_parent = $1 OR pollen_parent = $1
+  UNION
+  SELECT details.*, progeny.generation + 1, progeny.path || details.id
+  FROM details
+  INNER JOIN progeny
+    ON details.seed_parent = progeny.id OR details.pollen_parent = progeny.id
+  WHERE ($2::INT IS NULL OR progeny.generation < $2)
+    AND NOT details.id = ANY(progeny.path)
+)
+SELECT DISTINCT ON (id)
+  id AS "id!",
+  registar_id AS "registar_id!",
+  genus AS "genus!",
+  epithet AS "epithet!",
+  synonym AS "synonym!",
+  synonym_genus,
+  synonym_epithet,
+  registrant_name,
+  originator_name,
+  date_of_registration,
+  seed_parent,
+  pollen_parent,
+  generation AS "generation!"
+FROM progeny
+ORDER BY id, generation;
@@ -0,0 +1,17 @@
+WITH RECURSIVE progeny AS (
+  SELECT details.id, details.date_of_registration, 1 AS generation
+  FROM details
+  WHERE seed_parent = $1 OR pollen_parent = $1
+  UNION
+  SELECT details.id, details.date_of_registration, progeny.generation + 1
+  FROM details
+  INNER JOIN progeny
+    ON details.seed_parent = progeny.id OR details.pollen_parent = progeny.id
+  WHERE $2::INT IS NULL OR progeny.generation < $2
+)
+SELECT
+  EXTRACT(YEAR FROM date_of_registration)::INT AS year,
+  COUNT(DISTINCT id) AS "count!"
+FROM progeny
+GROUP BY year
+ORDER BY year;
@@ -0,0 +1,30 @@
+SELECT
+  details.registar_id,
+  genera.name AS genus,
+  details.epithet,
+  details.synonym,
+  synonym_genera.name AS "synonym_genus?",
+  synonyms.epithet AS "synonym_epithet?",
+  details.registrant_name,
+  details.originator_name,
+  details.date_of_registration,
+  seed_genera.name AS "seed_genus?",
+  seed_parents.epithet AS "seed_epithet?",
+  pollen_genera.name AS "pollen_genus?",
+  pollen_parents.epithet AS "pollen_epithet?"
+FROM details
+INNER JOIN genera
+  ON genera.id = details.genus
+LEFT JOIN genera AS synonym_genera
+  ON synonym_genera.id = details.synonym_genus
+LEFT JOIN details AS synonyms
+  ON synonyms.id = details.synonym_epithet
+LEFT JOIN details AS seed_parents
+  ON seed_parents.id = details.seed_parent
+LEFT JOIN genera AS seed_genera
+  ON seed_genera.id = seed_parents.genus
+LEFT JOIN details AS pollen_parents
+  ON pollen_parents.id = details.pollen_parent
+LEFT JOIN genera AS pollen_genera
+  ON pollen_genera.id = pollen_parents.genus
+WHERE details.registar_id = $1;
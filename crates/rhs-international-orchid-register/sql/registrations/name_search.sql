@@ -0,0 +1,10 @@
+SELECT
+  genera.name AS genus,
+  details.epithet AS grex,
+  details.registar_id AS id
+FROM details
+INNER JOIN genera
+  ON genera.id = details.genus
+WHERE ($1::TEXT IS NULL OR genera.name ILIKE $1)
+  AND ($2::TEXT IS NULL OR details.epithet ILIKE $2)
+ORDER BY genera.name, details.epithet, details.registar_id;
@@ -0,0 +1,20 @@
+SELECT
+  genera.name AS genus,
+  details.epithet AS grex,
+  details.registar_id AS id
+FROM details
+INNER JOIN genera
+  ON genera.id = details.genus
+LEFT JOIN details AS seed_parents
+  ON seed_parents.id = details.seed_parent
+LEFT JOIN genera AS seed_genera
+  ON seed_genera.id = seed_parents.genus
+LEFT JOIN details AS pollen_parents
+  ON pollen_parents.id = details.pollen_parent
+LEFT JOIN genera AS pollen_genera
+  ON pollen_genera.id = pollen_parents.genus
+WHERE ($1::TEXT IS NULL OR seed_genera.name ILIKE $1)
+  AND ($2::TEXT IS NULL OR seed_parents.epithet ILIKE $2)
+  AND ($3::TEXT IS NULL OR pollen_genera.name ILIKE $3)
+  AND ($4::TEXT IS NULL OR pollen_parents.epithet ILIKE $4)
+ORDER BY genera.name, details.epithet, details.registar_id;
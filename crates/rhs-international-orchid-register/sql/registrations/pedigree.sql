@@ -0,0 +1,45 @@
+WITH RECURSIVE pedigree AS (
+  SELECT details.id, 0 AS generation, ARRAY[details.id] AS path
+  FROM details
+  WHERE registar_id = $1
+  UNION
+  SELECT parents.id, pedigree.generation + 1, pedigree.path || parents.id
+  FROM pedigree
+  INNER JOIN details AS children
+    ON children.id = pedigree.id
+  INNER JOIN details AS parents
+    ON parents.id = children.seed_parent OR parents.id = children.pollen_parent
+  WHERE ($2::INT IS NULL OR pedigree.generation < $2)
+    AND NOT parents.id = ANY(pedigree.path)
+)
+SELECT DISTINCT ON (details.id)
+  details.registar_id AS "registar_id!",
+  genera.name AS "genus!",
+  details.epithet AS "epithet!",
+  details.synonym AS "synonym!",
+  synonym_genera.name AS "synonym_genus?",
+  synonyms.epithet AS "synonym_epithet?",
+  details.registrant_name,
+  details.originator_name,
+  details.date_of_registration,
+  seed_genera.name AS "seed_genus?",
+  seed_parents.epithet AS "seed_epithet?",
+  pollen_genera.name AS "pollen_genus?",
+  pollen_parents.epithet AS "pollen_epithet?"
+FROM pedigree
+INNER JOIN details
+  ON details.id = pedigree.id
+INNER JOIN genera
+  ON genera.id = details.genus
+LEFT JOIN genera AS synonym_genera
+  ON synonym_genera.id = details.synonym_genus
+LEFT JOIN details AS synonyms
+  ON synonyms.id = details.synonym_epithet
+LEFT JOIN details AS seed_parents
+  ON seed_parents.id = details.seed_parent
+LEFT JOIN genera AS seed_genera
+  ON seed_genera.id = seed_parents.genus
+LEFT JOIN details AS pollen_parents
+  ON pollen_parents.id = details.pollen_parent
+LEFT JOIN genera AS pollen_genera
+  ON pollen_genera.id = pollen_parents.genus;
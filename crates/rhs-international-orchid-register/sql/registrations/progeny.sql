@@ -0,0 +1,47 @@
+WITH RECURSIVE progeny AS (
+  SELECT details.id, 1 AS generation, ARRAY[parents.id, details.id] AS path
+  FROM details
+  INNER JOIN details AS parents
+    ON parents.id = details.seed_parent OR parents.id = details.pollen_parent
+  WHERE parents.registar_id = $1
+  UNION
+  SELECT details.id, progeny.generation + 1, progeny.path || details.id
+  FROM details
+  INNER JOIN progeny
+    ON details.seed_parent = progeny.id OR details.pollen_parent = progeny.id
+  WHERE ($2::INT IS NULL OR progeny.generation < $2)
+    AND NOT details.id = ANY(progeny.path)
+)
+SELECT DISTINCT ON (details.id)
+  progeny.generation AS "generation!",
+  details.registar_id AS "registar_id!",
+  genera.name AS "genus!",
+  details.epithet AS "epithet!",
+  details.synonym AS "synonym!",
+  synonym_genera.name AS "synonym_genus?",
+  synonyms.epithet AS "synonym_epithet?",
+  details.registrant_name,
+  details.originator_name,
+  details.date_of_registration,
+  seed_genera.name AS "seed_genus?",
+  seed_parents.epithet AS "seed_epithet?",
+  pollen_genera.name AS "pollen_genus?",
+  pollen_parents.epithet AS "pollen_epithet?"
+FROM progeny
+INNER JOIN details
+  ON details.id = progeny.id
+INNER JOIN genera
+  ON genera.id = details.genus
+LEFT JOIN genera AS synonym_genera
+  ON synonym_genera.id = details.synonym_genus
+LEFT JOIN details AS synonyms
+  ON synonyms.id = details.synonym_epithet
+LEFT JOIN details AS seed_parents
+  ON seed_parents.id = details.seed_parent
+LEFT JOIN genera AS seed_genera
+  ON seed_genera.id = seed_parents.genus
+LEFT JOIN details AS pollen_parents
+  ON pollen_parents.id = details.pollen_parent
+LEFT JOIN genera AS pollen_genera
+  ON pollen_genera.id = pollen_parents.genus
+ORDER BY details.id, progeny.generation;
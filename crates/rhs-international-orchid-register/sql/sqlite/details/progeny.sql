@@ -0,0 +1,18 @@
+WITH RECURSIVE progeny (id, generation, path) AS (
+  SELECT details.id, 1, ',' || ?1 || ',' || details.id || ','
+  FROM details
+  WHERE seed_parent = ?1 OR pollen_parent = ?1
+  UNION
+  SELECT details.id, progeny.generation + 1, progeny.path || details.id || ','
+  FROM details
+  INNER JOIN progeny
+    ON details.seed_parent = progeny.id OR details.pollen_parent = progeny.id
+  WHERE (?2 IS NULL OR progeny.generation < ?2)
+    AND instr(progeny.path, ',' || details.id || ',') = 0
+)
+SELECT details.*, MIN(progeny.generation) AS generation
+FROM progeny
+INNER JOIN details
+  ON details.id = progeny.id
+GROUP BY details.id
+ORDER BY details.id;
@@ -0,0 +1,20 @@
+WITH RECURSIVE progeny (id, generation, path) AS (
+  SELECT details.id, 1, ',' || ?1 || ',' || details.id || ','
+  FROM details
+  WHERE seed_parent = ?1 OR pollen_parent = ?1
+  UNION
+  SELECT details.id, progeny.generation + 1, progeny.path || details.id || ','
+  FROM details
+  INNER JOIN progeny
+    ON details.seed_parent = progeny.id OR details.pollen_parent = progeny.id
+  WHERE (?2 IS NULL OR progeny.generation < ?2)
+    AND instr(progeny.path, ',' || details.id || ',') = 0
+)
+SELECT
+  CAST(strftime('%Y', details.date_of_registration) AS INTEGER) AS year,
+  COUNT(DISTINCT details.id) AS count
+FROM progeny
+INNER JOIN details
+  ON details.id = progeny.id
+GROUP BY year
+ORDER BY year;
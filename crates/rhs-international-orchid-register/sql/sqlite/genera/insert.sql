@@ -0,0 +1 @@
+INSERT INTO genera ( name ) VALUES ( ?1 ) RETURNING *;
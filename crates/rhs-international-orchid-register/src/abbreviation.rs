@@ -0,0 +1,142 @@
+//! The RHS's standard abbreviations for genus names, like `Phal.` for Phalaenopsis.
+
+use std::{collections::HashMap, sync::OnceLock};
+
+use crate::{
+  api::Details,
+  table::{rows, ParseError},
+};
+
+const ABBREVIATIONS: &str = include_str!("../data/genus_abbreviations.csv");
+
+#[derive(Debug, Clone, Default)]
+pub struct Abbreviations {
+  /// Keyed by the lowercased abbreviation without its full stop.
+  genera: HashMap<String, String>,
+  abbreviations: HashMap<String, String>,
+}
+
+impl Abbreviations {
+  /// The table checked in to `data/genus_abbreviations.csv`.
+  pub fn bundled() -> &'static Self {
+    static BUNDLED: OnceLock<Abbreviations> = OnceLock::new();
+
+    BUNDLED.get_or_init(|| Self::parse(ABBREVIATIONS).expect("bundled abbreviations"))
+  }
+
+  /// Reads a table in the same format as the bundled one: a `genus,abbreviation` header, then
+  /// one genus per line. Lines starting with `#` are skipped.
+  pub fn parse(table: &str) -> Result<Self, ParseError> {
+    let mut s = Self::default();
+
+    for (line, genus, abbreviation) in rows(table)? {
+      let key = key(abbreviation);
+
+      if let Some(existing) = s.genera.get(&key).filter(|existing| *existing != genus) {
+        return Err(ParseError {
+          line,
+          message: format!("{abbreviation} is already used for {existing}"),
+        });
+      }
+
+      s.genera.insert(key, genus.to_string());
+      s.abbreviations
+        .insert(genus.to_string(), abbreviation.to_string());
+    }
+
+    Ok(s)
+  }
+
+  /// The genus `name` abbreviates, matched ignoring case. Only names ending in a full stop are
+  /// abbreviations, so anything else, including bare initials like `C`, is returned as it is.
+  pub fn expand<'a>(&'a self, name: &'a str) -> &'a str {
+    let name = name.trim();
+
+    if !name.ends_with('.') {
+      return name;
+    }
+
+    self.genera.get(&key(name)).map_or(name, String::as_str)
+  }
+
+  /// The abbreviation for `genus`, or `genus` itself if it doesn't have one.
+  pub fn abbreviate<'a>(&'a self, genus: &'a str) -> &'a str {
+    let genus = genus.trim();
+
+    self.abbreviations.get(genus).map_or(genus, String::as_str)
+  }
+
+  /// Splits a name like `Phal. Doris` or `Phalaenopsis Doris` into its full genus and epithet.
+  pub fn parse_name<'a>(&'a self, name: &'a str) -> Option<(&'a str, &'a str)> {
+    let (genus, epithet) = name.trim().split_once(char::is_whitespace)?;
+    let epithet = epithet.trim();
+
+    (!epithet.is_empty()).then(|| (self.expand(genus), epithet))
+  }
+
+  /// The abbreviated name to print on a label, like `Phal. Doris`.
+  pub fn label(&self, details: &Details) -> String {
+    format!("{} {}", self.abbreviate(&details.genus), details.epithet)
+  }
+}
+
+fn key(abbreviation: &str) -> String {
+  abbreviation.trim().trim_end_matches('.').to_lowercase()
+}
+
+/// Sends a genus to the register in full, as it doesn't understand abbreviations.
+#[cfg(feature = "scrape")]
+pub(crate) fn serialize_genus<S: serde::Serializer>(
+  genus: &Option<String>,
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  let genus = genus
+    .as_deref()
+    .map(|genus| Abbreviations::bundled().expand(genus));
+
+  serde::Serialize::serialize(&genus, serializer)
+}
+
+#[test]
+fn bundled() {
+  let table = Abbreviations::bundled();
+
+  assert_eq!(table.expand("Phal."), "Phalaenopsis");
+  assert_eq!(table.expand("rlc."), "Rhyncholaeliocattleya");
+  assert_eq!(table.expand(" Den. "), "Dendrobium");
+  assert_eq!(table.expand("rlc"), "rlc");
+  assert_eq!(table.expand("C"), "C");
+  assert_eq!(table.expand("V"), "V");
+  assert_eq!(table.expand("Paphiopedilum"), "Paphiopedilum");
+  assert_eq!(table.expand("Unknown."), "Unknown.");
+
+  assert_eq!(table.abbreviate("Paphiopedilum"), "Paph.");
+  assert_eq!(table.abbreviate("Disa"), "Disa");
+
+  assert_eq!(
+    table.parse_name("Phal.  Golden Sands"),
+    Some(("Phalaenopsis", "Golden Sands"))
+  );
+  assert_eq!(
+    table.parse_name("Cattleya labiata"),
+    Some(("Cattleya", "labiata"))
+  );
+  assert_eq!(table.parse_name("Phal."), None);
+
+  let details = Details {
+    id: 1,
+    genus: "Rhyncholaeliocattleya".into(),
+    epithet: "Memoria Helen Brown".into(),
+    synonym: false,
+    synonym_details: None,
+    registrant_name: None,
+    originator_name: None,
+    date_of_registration: None,
+    seed_parent: None,
+    pollen_parent: None,
+  };
+  assert_eq!(table.label(&details), "Rlc. Memoria Helen Brown");
+
+  let err = Abbreviations::parse("genus,abbreviation\nCattleya,C.\nCymbidium,C.\n").unwrap_err();
+  assert_eq!(err.line, 3);
+}
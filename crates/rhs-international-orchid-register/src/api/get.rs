@@ -1,24 +1,33 @@
+#[cfg(feature = "scrape")]
+use reqwest::StatusCode;
+#[cfg(feature = "scrape")]
 use scraper::{Html, Selector};
-use time::{macros::format_description, Date};
+#[cfg(feature = "scrape")]
+use time::macros::format_description;
+use time::Date;
 
-#[derive(Clone, Copy, Debug, Default, serde::Serialize)]
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Get {
   pub id: u32,
 }
 
-#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq, Hash, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Parent {
   pub genus: String,
   pub epithet: String,
 }
 
-#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq, Hash, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Synonym {
   pub genus: String,
   pub epithet: Option<String>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Details {
   pub id: u32,
   pub genus: String,
@@ -32,6 +41,7 @@ pub struct Details {
   pub pollen_parent: Option<Parent>,
 }
 
+#[cfg(feature = "scrape")]
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
   #[error("Not found")]
@@ -42,17 +52,36 @@ pub enum Error {
   Time(#[from] time::error::Parse),
 }
 
+#[cfg(feature = "scrape")]
+impl Error {
+  pub fn is_transient(&self) -> bool {
+    match self {
+      Error::Reqwest(e) => icra_http::is_transient(e),
+      Error::NotFound | Error::Time(_) => false,
+    }
+  }
+}
+
+#[cfg(feature = "scrape")]
 impl Get {
   pub async fn lookup(&self) -> Result<Details, Error> {
-    let client = reqwest::Client::new();
+    self.lookup_with(icra_http::Client::shared()).await
+  }
 
+  pub async fn lookup_with(&self, client: &icra_http::Client) -> Result<Details, Error> {
     let res = client
-      .get("https://apps.rhs.org.uk/horticulturaldatabase/orchidregister/orchiddetails.asp")
-      .query(&[("ID", self.id)])
-      .send()
+      .send(
+        client
+          .get("https://apps.rhs.org.uk/horticulturaldatabase/orchidregister/orchiddetails.asp")
+          .query(&[("ID", self.id)]),
+      )
       .await?;
 
-    let html = res.text().await?;
+    if res.status() == StatusCode::NOT_FOUND {
+      Err(Error::NotFound)?;
+    }
+
+    let html = res.error_for_status()?.text().await?;
 
     let html = Html::parse_document(&html);
 
@@ -60,16 +89,30 @@ impl Get {
   }
 }
 
+#[cfg(feature = "blocking")]
+impl Get {
+  pub fn lookup_blocking(&self) -> Result<Details, Error> {
+    icra_http::block_on(self.lookup())
+  }
+
+  pub fn lookup_blocking_with(&self, client: &icra_http::Client) -> Result<Details, Error> {
+    icra_http::block_on(self.lookup_with(client))
+  }
+}
+
+#[cfg(feature = "scrape")]
 fn to_string(el: scraper::ElementRef<'_>) -> String {
   let text = el.text().map(|t| t.to_string()).collect::<String>();
 
   text.trim().to_string()
 }
 
+#[cfg(feature = "scrape")]
 fn name_exists(name: &str) -> bool {
   !matches!(name, "O/U" | "Unknown" | "")
 }
 
+#[cfg(feature = "scrape")]
 fn parse(id: u32, html: Html) -> Result<Details, Error> {
   let table = Selector::parse("table.results").unwrap();
   let tbody = Selector::parse("tbody").unwrap();
@@ -181,6 +224,7 @@ fn parse(id: u32, html: Html) -> Result<Details, Error> {
   Ok(details)
 }
 
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn latest() -> Result<(), Box<dyn std::error::Error>> {
   let mut id = 1064207;
@@ -205,6 +249,7 @@ async fn latest() -> Result<(), Box<dyn std::error::Error>> {
   Ok(())
 }
 
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn species() -> Result<(), Box<dyn std::error::Error>> {
   let get: Get = Get { id: 1437 };
@@ -215,6 +260,18 @@ async fn species() -> Result<(), Box<dyn std::error::Error>> {
   Ok(())
 }
 
+#[cfg(feature = "blocking")]
+#[test]
+fn species_blocking() -> Result<(), Box<dyn std::error::Error>> {
+  let res = Get { id: 1437 }.lookup_blocking()?;
+
+  assert_eq!(res.id, 1437);
+  assert!(!res.genus.is_empty());
+
+  Ok(())
+}
+
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn species_synonym() -> Result<(), Box<dyn std::error::Error>> {
   let get: Get = Get { id: 1475 };
@@ -225,6 +282,7 @@ async fn species_synonym() -> Result<(), Box<dyn std::error::Error>> {
   Ok(())
 }
 
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn hybrid() -> Result<(), Box<dyn std::error::Error>> {
   let get = Get { id: 1064207 };
@@ -235,6 +293,7 @@ async fn hybrid() -> Result<(), Box<dyn std::error::Error>> {
   Ok(())
 }
 
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn hybrid_synonym() -> Result<(), Box<dyn std::error::Error>> {
   let get = Get { id: 134249 };
@@ -1,10 +1,15 @@
 pub(crate) mod get;
-mod parentage;
-mod search;
+pub mod parentage;
+pub mod search;
 
-pub use get::{Details, Get};
+pub use get::{Details, Get, Parent, Synonym};
+#[cfg(feature = "scrape")]
+pub use icra_http::Client;
+pub use parentage::ParentageSearch;
+pub use search::OnOff;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SearchResult {
   pub genus: String,
   pub grex: String,
@@ -16,11 +21,33 @@ pub enum Search {
   Name(search::Search),
 }
 
+#[cfg(feature = "scrape")]
 impl Search {
   pub async fn search(&self) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
+    self.search_with(icra_http::Client::shared()).await
+  }
+
+  pub async fn search_with(
+    &self,
+    client: &icra_http::Client,
+  ) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
     match self {
-      Search::Parentage(parentage_search) => parentage_search.search().await,
-      Search::Name(search) => search.search().await,
+      Search::Parentage(parentage_search) => parentage_search.search_with(client).await,
+      Search::Name(search) => search.search_with(client).await,
     }
   }
 }
+
+#[cfg(feature = "blocking")]
+impl Search {
+  pub fn search_blocking(&self) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
+    icra_http::block_on(self.search())
+  }
+
+  pub fn search_blocking_with(
+    &self,
+    client: &icra_http::Client,
+  ) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
+    icra_http::block_on(self.search_with(client))
+  }
+}
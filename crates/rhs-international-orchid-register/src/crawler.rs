@@ -0,0 +1,518 @@
+use std::{future::Future, ops::RangeInclusive, path::PathBuf};
+
+use tokio::task::JoinSet;
+
+use crate::{
+  api::{get, Client, Details, Get},
+  csv::{Dump, DumpError, Journal, JournalEntry, Reason},
+};
+
+#[derive(Clone, Debug)]
+pub struct Config {
+  pub start: u32,
+  pub end: u32,
+  pub concurrency: usize,
+  pub chunk: u32,
+  pub checkpoint_every: usize,
+  /// How many ids in a row past the newest registration that can't be fetched end a search for
+  /// new ones.
+  pub max_gap: u32,
+  pub data: PathBuf,
+  pub known_bad: PathBuf,
+  /// Checkpoints append to this log instead of rewriting `data` and `known_bad`, which are only
+  /// rewritten every [`Config::compact_every`] checkpoints and when a crawl finishes.
+  pub log: Option<PathBuf>,
+  pub compact_every: usize,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      start: 816,
+      end: 1_066_954,
+      concurrency: 10,
+      chunk: 10,
+      checkpoint_every: 1,
+      max_gap: 50,
+      data: PathBuf::from("dump.csv"),
+      known_bad: PathBuf::from("known_bad.csv"),
+      log: None,
+      compact_every: 1000,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct Summary {
+  pub fetched: Vec<u32>,
+  pub not_found: Vec<u32>,
+  pub transient: Vec<u32>,
+  pub errored: Vec<u32>,
+  pub interrupted: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+  #[error(transparent)]
+  Dump(#[from] DumpError),
+  #[error(transparent)]
+  Join(#[from] tokio::task::JoinError),
+}
+
+pub struct Crawler {
+  config: Config,
+  dump: Dump,
+  client: Client,
+  journal: Option<Journal>,
+  /// Changes since the last checkpoint, waiting to be appended to the journal.
+  pending: Vec<JournalEntry>,
+}
+
+type Chunk = Vec<(u32, Result<Details, get::Error>)>;
+
+impl Crawler {
+  pub fn new(config: Config, dump: Dump) -> Self {
+    Self {
+      config,
+      dump,
+      client: Client::shared().clone(),
+      journal: None,
+      pending: Vec::new(),
+    }
+  }
+
+  /// Sends lookups through `client` instead of the process-wide default.
+  pub fn with_client(mut self, client: Client) -> Self {
+    self.client = client;
+    self
+  }
+
+  pub async fn from_files(config: Config) -> Result<Self, Error> {
+    let mut dump = if tokio::fs::try_exists(&config.data).await.unwrap_or(false) {
+      if tokio::fs::try_exists(&config.known_bad)
+        .await
+        .unwrap_or(false)
+      {
+        Dump::from_data_and_known_bad_files(&config.data, &config.known_bad).await?
+      } else {
+        Dump::from_data_file(&config.data).await?
+      }
+    } else {
+      Dump::empty()
+    };
+
+    let journal = match &config.log {
+      Some(log) => {
+        dump.replay(log).await?;
+        Some(Journal::open(log).await?)
+      },
+      None => None,
+    };
+
+    Ok(Self {
+      journal,
+      ..Self::new(config, dump)
+    })
+  }
+
+  pub fn dump(&self) -> &Dump {
+    &self.dump
+  }
+
+  pub fn into_dump(self) -> Dump {
+    self.dump
+  }
+
+  pub async fn run(&mut self) -> Result<Summary, Error> {
+    self.run_until(std::future::pending()).await
+  }
+
+  pub async fn run_until(&mut self, shutdown: impl Future<Output = ()>) -> Result<Summary, Error> {
+    let range = self.config.start..=self.config.end;
+
+    self
+      .crawl(
+        range,
+        |dump, get| !dump.has(get) && !dump.is_known_bad(get),
+        shutdown,
+      )
+      .await
+  }
+
+  /// Looks up every known-bad id in `range` again, whatever the reason it was recorded with.
+  pub async fn reverify(&mut self, range: RangeInclusive<u32>) -> Result<Summary, Error> {
+    self.reverify_until(range, std::future::pending()).await
+  }
+
+  pub async fn reverify_until(
+    &mut self,
+    range: RangeInclusive<u32>,
+    shutdown: impl Future<Output = ()>,
+  ) -> Result<Summary, Error> {
+    self
+      .crawl(
+        range,
+        |dump, get| dump.is_known_bad(get) || dump.is_transient(get),
+        shutdown,
+      )
+      .await
+  }
+
+  /// Looks for ids assigned since the last crawl by probing upwards from [`Dump::last`] until
+  /// [`Config::max_gap`] ids in a row can't be fetched, and returns the registrations it found.
+  ///
+  /// Missing ids aren't recorded as known-bad, as they may just not have been assigned yet.
+  pub async fn new_registrations(&mut self) -> Result<Vec<Details>, Error> {
+    let start = self.dump.last().saturating_add(1).max(self.config.start);
+    let client = self.client.clone();
+
+    let results = discover(
+      start,
+      self.config.max_gap,
+      self.config.concurrency,
+      move |id| {
+        let client = client.clone();
+        async move { Get { id }.lookup_with(&client).await }
+      },
+    )
+    .await?;
+
+    let mut new = Vec::new();
+
+    for (id, details) in results {
+      match details {
+        Ok(details) => {
+          self.record(JournalEntry::Found(Box::new(details.clone())));
+          new.push(details);
+        },
+        Err(e) if e.is_transient() => self.record(JournalEntry::KnownBad(id, Reason::Transient)),
+        Err(_) => (),
+      }
+    }
+
+    self.checkpoint().await?;
+
+    Ok(new)
+  }
+
+  async fn crawl(
+    &mut self,
+    range: RangeInclusive<u32>,
+    wanted: impl Fn(&Dump, Get) -> bool,
+    shutdown: impl Future<Output = ()>,
+  ) -> Result<Summary, Error> {
+    let mut tasks: JoinSet<Chunk> = JoinSet::new();
+    let mut summary = Summary::default();
+
+    let (first, last) = range.into_inner();
+    let mut next = Some(first).filter(|first| *first <= last);
+    let mut since_checkpoint = 0;
+    let mut shutdown = std::pin::pin!(shutdown);
+
+    loop {
+      while !summary.interrupted && tasks.len() < self.config.concurrency.max(1) {
+        let Some(start) = next else {
+          break;
+        };
+
+        let end = start.saturating_add(self.config.chunk.max(1) - 1).min(last);
+        next = end.checked_add(1).filter(|next| *next <= last);
+
+        let ids = (start..=end)
+          .map(|id| Get { id })
+          .filter(|get| wanted(&self.dump, *get))
+          .map(|get| get.id)
+          .collect::<Vec<_>>();
+
+        if ids.is_empty() {
+          continue;
+        }
+
+        let client = self.client.clone();
+        tasks.spawn(async move {
+          let mut res = Vec::with_capacity(ids.len());
+
+          for id in ids {
+            res.push((id, Get { id }.lookup_with(&client).await));
+          }
+
+          res
+        });
+      }
+
+      if tasks.is_empty() {
+        break;
+      }
+
+      tokio::select! {
+        _ = &mut shutdown, if !summary.interrupted => summary.interrupted = true,
+        Some(res) = tasks.join_next() => {
+          let res = match res {
+            Ok(res) => res,
+            Err(e) => {
+              // keep what the other tasks have fetched so far
+              self.checkpoint().await?;
+              return Err(e.into());
+            },
+          };
+
+          for (id, details) in res {
+            match details {
+              Ok(details) => {
+                self.record(JournalEntry::Found(Box::new(details)));
+                summary.fetched.push(id);
+              },
+              Err(get::Error::NotFound) => {
+                self.record(JournalEntry::KnownBad(id, Reason::NotFound));
+                summary.not_found.push(id);
+              },
+              Err(e) if e.is_transient() => {
+                self.record(JournalEntry::KnownBad(id, Reason::Transient));
+                summary.transient.push(id);
+              },
+              Err(_) => summary.errored.push(id),
+            }
+          }
+
+          since_checkpoint += 1;
+          if since_checkpoint >= self.config.checkpoint_every {
+            self.checkpoint().await?;
+            since_checkpoint = 0;
+          }
+        },
+      }
+    }
+
+    self.compact().await?;
+
+    summary.fetched.sort();
+    summary.not_found.sort();
+    summary.transient.sort();
+    summary.errored.sort();
+
+    Ok(summary)
+  }
+
+  fn record(&mut self, entry: JournalEntry) {
+    if self.journal.is_some() {
+      self.pending.push(entry.clone());
+    }
+
+    self.dump.apply(entry);
+  }
+
+  /// Saves progress, appending to the journal if there is one and otherwise rewriting the dump.
+  pub async fn checkpoint(&mut self) -> Result<(), Error> {
+    let Some(journal) = &mut self.journal else {
+      return self.compact().await;
+    };
+
+    journal.append(&self.pending).await?;
+    self.pending.clear();
+
+    if journal.appends() >= self.config.compact_every.max(1) {
+      self.compact().await?;
+    }
+
+    Ok(())
+  }
+
+  /// Rewrites the dump's CSV files and empties the journal.
+  pub async fn compact(&mut self) -> Result<(), Error> {
+    self.pending.clear();
+
+    match &mut self.journal {
+      Some(journal) => {
+        journal
+          .compact(&self.dump, &self.config.data, &self.config.known_bad)
+          .await?
+      },
+      None => {
+        self
+          .dump
+          .write(&self.config.data, &self.config.known_bad)
+          .await?
+      },
+    }
+
+    Ok(())
+  }
+}
+
+/// Looks up ids from `start` in batches, stopping once `max_gap` consecutive ids can't be
+/// fetched, whether they weren't found or the lookup failed, so an outage can't keep it going.
+async fn discover<F, Fut>(
+  start: u32,
+  max_gap: u32,
+  batch: usize,
+  lookup: F,
+) -> Result<Vec<(u32, Result<Details, get::Error>)>, Error>
+where
+  F: Fn(u32) -> Fut,
+  Fut: Future<Output = Result<Details, get::Error>> + Send + 'static,
+{
+  let batch = u32::try_from(batch.max(1)).unwrap_or(u32::MAX);
+  let mut results = Vec::new();
+  let mut missing = 0;
+  let mut next = Some(start);
+
+  while let Some(start) = next.filter(|_| missing < max_gap.max(1)) {
+    let end = start.saturating_add(batch - 1);
+    next = end.checked_add(1);
+
+    let mut tasks = JoinSet::new();
+    for id in start..=end {
+      let lookup = lookup(id);
+      tasks.spawn(async move { (id, lookup.await) });
+    }
+
+    let mut chunk = Vec::with_capacity(tasks.len());
+    while let Some(res) = tasks.join_next().await {
+      chunk.push(res?);
+    }
+    chunk.sort_by_key(|(id, _)| *id);
+
+    for (id, details) in chunk {
+      match &details {
+        Ok(_) => missing = 0,
+        Err(_) => missing += 1,
+      }
+
+      results.push((id, details));
+
+      if missing >= max_gap.max(1) {
+        break;
+      }
+    }
+  }
+
+  Ok(results)
+}
+
+#[tokio::test]
+async fn discovers_past_gaps() -> Result<(), Error> {
+  let assigned = [101, 102, 105, 109, 115];
+
+  let results = discover(101, 4, 3, move |id| async move {
+    if id == 103 {
+      Err(get::Error::Time(time::error::Parse::TryFromParsed(
+        time::error::TryFromParsed::InsufficientInformation,
+      )))
+    } else if assigned.contains(&id) {
+      Ok(Details {
+        id,
+        genus: "Phalaenopsis".into(),
+        epithet: id.to_string(),
+        synonym: false,
+        synonym_details: None,
+        registrant_name: None,
+        originator_name: None,
+        date_of_registration: None,
+        seed_parent: None,
+        pollen_parent: None,
+      })
+    } else {
+      Err(get::Error::NotFound)
+    }
+  })
+  .await?;
+
+  let found = results
+    .iter()
+    .filter_map(|(id, details)| details.is_ok().then_some(*id))
+    .collect::<Vec<_>>();
+
+  assert_eq!(found, [101, 102, 105, 109]);
+  // the search stops after 110..=113, so the batch's 114 is never considered
+  assert_eq!(results.last().map(|(id, _)| *id), Some(113));
+
+  Ok(())
+}
+
+#[tokio::test]
+async fn discovery_stops_when_lookups_fail() -> Result<(), Error> {
+  // nothing listens on port 1, so every lookup fails to connect
+  let results = discover(1, 5, 3, |_| async {
+    let e = reqwest::get("http://127.0.0.1:1").await.unwrap_err();
+    Err(get::Error::Reqwest(e))
+  })
+  .await?;
+
+  assert_eq!(results.len(), 5);
+  assert!(results
+    .iter()
+    .all(|(_, details)| details.as_ref().is_err_and(get::Error::is_transient)));
+
+  Ok(())
+}
+
+#[tokio::test]
+async fn skips_known() -> Result<(), Error> {
+  let dir = tempfile::tempdir().unwrap();
+  let dir = dir.path();
+
+  let mut dump = Dump::empty();
+  for id in 0..10 {
+    dump.insert_known_bad(id, Reason::NotFound);
+  }
+
+  let mut crawler = Crawler::new(
+    Config {
+      start: 0,
+      end: 9,
+      data: dir.join("dump.csv"),
+      known_bad: dir.join("known_bad.csv"),
+      ..Default::default()
+    },
+    dump,
+  );
+
+  let summary = crawler.run().await?;
+
+  assert!(summary.fetched.is_empty());
+  assert!(summary.not_found.is_empty());
+  assert!(!summary.interrupted);
+  assert!(Crawler::from_files(crawler.config.clone())
+    .await?
+    .dump()
+    .is_known_bad(Get { id: 9 }));
+
+  Ok(())
+}
+
+#[tokio::test]
+async fn resumes_from_log() -> Result<(), Error> {
+  let dir = tempfile::tempdir().unwrap();
+  let dir = dir.path();
+
+  let config = Config {
+    data: dir.join("dump.csv"),
+    known_bad: dir.join("known_bad.csv"),
+    log: Some(dir.join("dump.log")),
+    ..Default::default()
+  };
+
+  let mut journal = Journal::open(dir.join("dump.log")).await?;
+  journal
+    .append(&[JournalEntry::KnownBad(7, Reason::NotFound)])
+    .await?;
+
+  let mut crawler = Crawler::from_files(config.clone()).await?;
+  assert!(crawler.dump().is_known_bad(Get { id: 7 }));
+  assert!(!tokio::fs::try_exists(&config.data).await.unwrap());
+
+  crawler.compact().await?;
+
+  let crawler = Crawler::from_files(config.clone()).await?;
+  assert!(crawler.dump().is_known_bad(Get { id: 7 }));
+  assert_eq!(
+    tokio::fs::read_to_string(&config.known_bad).await.unwrap(),
+    "7\n"
+  );
+  assert!(tokio::fs::read(dir.join("dump.log"))
+    .await
+    .unwrap()
+    .is_empty());
+
+  Ok(())
+}
@@ -1,51 +1,66 @@
-use std::{
-  collections::{HashMap, HashSet},
-  path::Path,
-};
+use std::{collections::HashMap, path::Path};
+
+use tokio::io::AsyncWriteExt;
 
 use crate::{
   api::{Details, Get},
-  csv::{serde::CsvDetails, KnownBad},
+  csv::{
+    read_known_bad,
+    serde::DateFormat,
+    stream::{write_atomic, write_atomic_async, DumpReader, DumpWriter, Replacement, Rows},
+    KnownBad,
+    KnownBadSet,
+    Reason,
+  },
 };
 
 #[derive(Clone, Debug)]
 pub struct Dump {
   data: HashMap<u32, Details>,
-  known_bad: HashSet<crate::csv::KnownBad>,
+  known_bad: KnownBadSet,
+  transient: KnownBadSet,
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
   #[error("This entry does not exist")]
   None,
+  #[cfg(feature = "scrape")]
   #[error(transparent)]
   Get(#[from] crate::api::get::Error),
   #[error(transparent)]
   Io(#[from] std::io::Error),
   #[error(transparent)]
   Csv(#[from] csv::Error),
+  #[error(transparent)]
+  Json(#[from] serde_json::Error),
 }
 
 impl Dump {
   pub fn empty() -> Self {
     Self {
       data: HashMap::new(),
-      known_bad: HashSet::new(),
+      known_bad: KnownBadSet::new(),
+      transient: KnownBadSet::new(),
     }
   }
 
+  /// Reads `path` a row at a time on tokio's blocking thread pool.
   pub async fn from_data_file(path: impl AsRef<Path>) -> Result<Self, Error> {
-    let path = path.as_ref();
-    let file = tokio::fs::read(path).await?;
-    let data: Result<Vec<CsvDetails>, _> =
-      csv::Reader::from_reader(&file[..]).deserialize().collect();
-    let data = data?;
+    let path = path.as_ref().to_path_buf();
 
-    let data = HashMap::from_iter(data.into_iter().map(|i| (i.0.id, i.0)));
+    spawn_blocking(move || Self::from_data_file_blocking(path)).await
+  }
+
+  pub fn from_data_file_blocking(path: impl AsRef<Path>) -> Result<Self, Error> {
+    let data = DumpReader::from_path(path)?
+      .map(|details| details.map(|details| (details.id, details)))
+      .collect::<Result<HashMap<_, _>, _>>()?;
 
     Ok(Self {
       data,
-      known_bad: HashSet::new(),
+      known_bad: KnownBadSet::new(),
+      transient: KnownBadSet::new(),
     })
   }
 
@@ -53,60 +68,128 @@ impl Dump {
     data: impl AsRef<Path>,
     known_bad: impl AsRef<Path>,
   ) -> Result<Self, Error> {
-    let mut s = Self::from_data_file(data).await?;
+    let data = data.as_ref().to_path_buf();
+    let known_bad = known_bad.as_ref().to_path_buf();
 
-    let known_bad = known_bad.as_ref();
-    let file = tokio::fs::read(known_bad).await?;
-    let data: Result<Vec<KnownBad>, _> = {
-      let mut reader = csv::ReaderBuilder::new()
-        .has_headers(false)
-        .from_reader(&file[..]);
+    spawn_blocking(move || Self::from_data_and_known_bad_files_blocking(data, known_bad)).await
+  }
 
-      reader.deserialize().collect()
-    };
-    let data = data?;
+  pub fn from_data_and_known_bad_files_blocking(
+    data: impl AsRef<Path>,
+    known_bad: impl AsRef<Path>,
+  ) -> Result<Self, Error> {
+    let mut s = Self::from_data_file_blocking(data)?;
 
-    let data = HashSet::from_iter(data.into_iter());
+    let file = std::fs::read(known_bad)?;
+    let data: Result<Vec<_>, _> = read_known_bad(&file[..]).collect();
+    let data = data?;
 
-    s.known_bad = data;
+    for (known_bad, reason) in data {
+      match reason {
+        Reason::NotFound => s.known_bad.extend([known_bad]),
+        Reason::Transient => s.transient.extend([known_bad]),
+      }
+    }
 
     Ok(s)
   }
 
+  /// Writes both files a row at a time, each to a temporary file that's then renamed over the
+  /// original, so an interrupted write leaves the previous files intact.
   pub async fn write(
     &self,
     data: impl AsRef<Path>,
     known_bad: impl AsRef<Path>,
   ) -> Result<(), Error> {
-    let path = data.as_ref();
+    self
+      .write_with_date_format(data, known_bad, DateFormat::default())
+      .await
+  }
 
-    let mut data = self.data.clone().into_values().collect::<Vec<_>>();
-    data.sort_by_key(|e| e.id);
+  pub async fn write_with_date_format(
+    &self,
+    data: impl AsRef<Path>,
+    known_bad: impl AsRef<Path>,
+    date_format: DateFormat,
+  ) -> Result<(), Error> {
+    let tmp = Replacement::new(data.as_ref());
+
+    let mut file = tokio::fs::File::create(tmp.tmp()).await?;
+    let mut rows = Rows::new(date_format);
 
-    let mut writer = csv::WriterBuilder::new().from_writer(Vec::new());
+    for details in self.sorted() {
+      rows.push(details)?;
 
-    for entry in data {
-      writer.serialize(CsvDetails(entry)).unwrap();
+      if let Some(chunk) = rows.take_chunk()? {
+        file.write_all(&chunk).await?;
+      }
     }
 
-    tokio::fs::write(path, writer.into_inner().unwrap()).await?;
+    if let Some(chunk) = rows.take(0)? {
+      file.write_all(&chunk).await?;
+    }
+
+    file.sync_all().await?;
+    drop(file);
+    tmp.persist_async().await?;
+
+    write_atomic_async(known_bad.as_ref(), &self.known_bad_csv()).await?;
+
+    Ok(())
+  }
+
+  pub fn write_blocking(
+    &self,
+    data: impl AsRef<Path>,
+    known_bad: impl AsRef<Path>,
+  ) -> Result<(), Error> {
+    self.write_with_date_format_blocking(data, known_bad, DateFormat::default())
+  }
 
-    let path = known_bad.as_ref();
+  pub fn write_with_date_format_blocking(
+    &self,
+    data: impl AsRef<Path>,
+    known_bad: impl AsRef<Path>,
+    date_format: DateFormat,
+  ) -> Result<(), Error> {
+    let mut writer = DumpWriter::create_with_date_format(data, date_format)?;
 
-    let mut data = self.known_bad.clone().into_iter().collect::<Vec<_>>();
+    for details in self.sorted() {
+      writer.write(details)?;
+    }
+
+    writer.finish()?;
+
+    write_atomic(known_bad.as_ref(), &self.known_bad_csv())?;
+
+    Ok(())
+  }
+
+  /// Every registration, ordered by id.
+  pub(super) fn sorted(&self) -> Vec<&Details> {
+    let mut data = self.data.values().collect::<Vec<_>>();
+    data.sort_by_key(|e| e.id);
+
+    data
+  }
+
+  fn known_bad_csv(&self) -> Vec<u8> {
+    let mut data = self.known_bad().collect::<Vec<_>>();
     data.sort();
 
     let mut writer = csv::WriterBuilder::new()
       .has_headers(false)
+      .flexible(true)
       .from_writer(Vec::new());
 
-    for entry in data {
-      writer.serialize(entry).unwrap();
+    for (entry, reason) in data {
+      match reason {
+        Reason::NotFound => writer.serialize((entry,)).unwrap(),
+        Reason::Transient => writer.serialize((entry, reason)).unwrap(),
+      }
     }
 
-    tokio::fs::write(path, writer.into_inner().unwrap()).await?;
-
-    Ok(())
+    writer.into_inner().unwrap()
   }
 
   pub fn has(&self, get: Get) -> bool {
@@ -121,6 +204,7 @@ impl Dump {
     self.data.get(&get.id)
   }
 
+  #[cfg(feature = "scrape")]
   pub async fn get_or_insert(&mut self, get: Get) -> Result<&Details, Error> {
     if self.is_known_bad(get) {
       Err(Error::None)
@@ -138,6 +222,37 @@ impl Dump {
     self.data.insert(details.id, details);
   }
 
+  pub fn insert_known_bad(&mut self, id: u32, reason: Reason) {
+    match reason {
+      Reason::NotFound => {
+        self.transient.remove(id);
+        self.known_bad.insert(id);
+      },
+      Reason::Transient if !self.is_known_bad(Get { id }) => {
+        self.transient.insert(id);
+      },
+      Reason::Transient => (),
+    }
+  }
+
+  pub fn remove_known_bad(&mut self, id: u32) {
+    self.known_bad.remove(id);
+    self.transient.remove(id);
+  }
+
+  pub fn known_bad(&self) -> impl Iterator<Item = (KnownBad, Reason)> + '_ {
+    self
+      .known_bad
+      .iter()
+      .map(|entry| (entry, Reason::NotFound))
+      .chain(
+        self
+          .transient
+          .iter()
+          .map(|entry| (entry, Reason::Transient)),
+      )
+  }
+
   pub fn iter(&self) -> impl Iterator<Item = &Details> {
     self.data.values()
   }
@@ -150,11 +265,94 @@ impl Dump {
     &mut self.data
   }
 
-  fn is_known_bad(&self, get: Get) -> bool {
-    self.known_bad.iter().any(|i| i.contains(get.id))
+  pub fn is_known_bad(&self, get: Get) -> bool {
+    self.known_bad.contains(get.id)
   }
+
+  pub fn is_transient(&self, get: Get) -> bool {
+    self.transient.contains(get.id)
+  }
+}
+
+/// Runs `f` where it's allowed to block, passing on any panic.
+async fn spawn_blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+  tokio::task::spawn_blocking(f)
+    .await
+    .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+}
+
+#[tokio::test]
+async fn known_bad_reasons() -> Result<(), Error> {
+  let dir = tempfile::tempdir()?;
+  let dir = dir.path();
+  tokio::fs::write(dir.join("dump.csv"), "").await?;
+  tokio::fs::write(
+    dir.join("known_bad.csv"),
+    "1..=5\n7,transient\n9,not_found\n",
+  )
+  .await?;
+
+  let mut dump =
+    Dump::from_data_and_known_bad_files(dir.join("dump.csv"), dir.join("known_bad.csv")).await?;
+
+  assert!(dump.is_known_bad(Get { id: 3 }));
+  assert!(dump.is_transient(Get { id: 7 }));
+  assert!(!dump.is_known_bad(Get { id: 7 }));
+
+  dump.remove_known_bad(3);
+  dump.insert_known_bad(7, Reason::NotFound);
+  dump.insert_known_bad(8, Reason::Transient);
+  dump
+    .write(dir.join("dump.csv"), dir.join("known_bad.csv"))
+    .await?;
+
+  let known_bad = tokio::fs::read_to_string(dir.join("known_bad.csv")).await?;
+  assert_eq!(known_bad, "1..=2\n4..=5\n7\n8,transient\n9\n");
+
+  dump.insert_known_bad(6, Reason::NotFound);
+  dump.insert_known_bad(8, Reason::NotFound);
+  dump
+    .write(dir.join("dump.csv"), dir.join("known_bad.csv"))
+    .await?;
+
+  let known_bad = tokio::fs::read_to_string(dir.join("known_bad.csv")).await?;
+  assert_eq!(known_bad, "1..=2\n4..=9\n");
+
+  Ok(())
+}
+
+#[test]
+fn blocking() -> Result<(), Error> {
+  let dir = tempfile::tempdir()?;
+  let dir = dir.path();
+  std::fs::write(dir.join("known_bad.csv"), "1..=3\n5,transient\n")?;
+
+  let mut dump = Dump::empty();
+  dump.insert(Details {
+    id: 4,
+    genus: "Phalaenopsis".into(),
+    epithet: "amabilis".into(),
+    synonym: false,
+    synonym_details: None,
+    registrant_name: None,
+    originator_name: None,
+    date_of_registration: None,
+    seed_parent: None,
+    pollen_parent: None,
+  });
+  dump.write_blocking(dir.join("dump.csv"), dir.join("empty.csv"))?;
+
+  let dump =
+    Dump::from_data_and_known_bad_files_blocking(dir.join("dump.csv"), dir.join("known_bad.csv"))?;
+
+  assert_eq!(dump.get(Get { id: 4 }).unwrap().epithet, "amabilis");
+  assert!(dump.is_known_bad(Get { id: 2 }));
+  assert!(dump.is_transient(Get { id: 5 }));
+
+  Ok(())
 }
 
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn save() -> Result<(), Error> {
   let mut dump = Dump::empty();
@@ -169,6 +367,7 @@ async fn save() -> Result<(), Error> {
   Ok(())
 }
 
+#[cfg(feature = "scrape")]
 #[tokio::test]
 async fn load_up_to() -> Result<(), Error> {
   let mut dump = Dump::from_data_and_known_bad_files("data/dump.csv", "data/known_bad.csv").await?;
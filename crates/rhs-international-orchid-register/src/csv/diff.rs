@@ -0,0 +1,247 @@
+use serde::{Serialize, Serializer};
+
+use crate::{
+  api::Details,
+  csv::{
+    serde::{fields, CsvDetailsRef, DateFormat},
+    Dump,
+  },
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FieldChange {
+  pub field: &'static str,
+  pub old: Option<String>,
+  pub new: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Modified<'a> {
+  pub id: u32,
+  #[serde(skip)]
+  pub old: &'a Details,
+  #[serde(serialize_with = "flat")]
+  pub new: &'a Details,
+  pub changes: Vec<FieldChange>,
+}
+
+/// Registrations added, removed and modified between two dumps, each sorted by id.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Diff<'a> {
+  #[serde(serialize_with = "flat_all")]
+  pub added: Vec<&'a Details>,
+  #[serde(serialize_with = "flat_all")]
+  pub removed: Vec<&'a Details>,
+  pub modified: Vec<Modified<'a>>,
+}
+
+impl Dump {
+  /// Compares `self` against a `newer` crawl of the register.
+  pub fn diff<'a>(&'a self, newer: &'a Dump) -> Diff<'a> {
+    let mut diff = Diff::default();
+
+    for old in self.iter() {
+      match newer.data().get(&old.id) {
+        None => diff.removed.push(old),
+        Some(new) => {
+          let changes = changes(old, new);
+
+          if !changes.is_empty() {
+            diff.modified.push(Modified {
+              id: old.id,
+              old,
+              new,
+              changes,
+            });
+          }
+        },
+      }
+    }
+
+    diff.added = newer
+      .iter()
+      .filter(|new| !self.data().contains_key(&new.id))
+      .collect();
+
+    diff.added.sort_by_key(|d| d.id);
+    diff.removed.sort_by_key(|d| d.id);
+    diff.modified.sort_by_key(|m| m.id);
+
+    diff
+  }
+}
+
+fn changes(old: &Details, new: &Details) -> Vec<FieldChange> {
+  fields(old)
+    .into_iter()
+    .zip(fields(new))
+    .filter(|((_, old), (_, new))| old != new)
+    .map(|((field, old), (_, new))| FieldChange { field, old, new })
+    .collect()
+}
+
+#[derive(Serialize)]
+struct Row<'a> {
+  change: &'static str,
+  id: u32,
+  genus: &'a str,
+  epithet: &'a str,
+  field: Option<&'static str>,
+  old: Option<&'a str>,
+  new: Option<&'a str>,
+}
+
+impl<'a> Row<'a> {
+  fn whole(change: &'static str, details: &'a Details) -> Self {
+    Self {
+      change,
+      id: details.id,
+      genus: &details.genus,
+      epithet: &details.epithet,
+      field: None,
+      old: None,
+      new: None,
+    }
+  }
+}
+
+impl Diff<'_> {
+  pub fn is_empty(&self) -> bool {
+    self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+  }
+
+  /// One row per added or removed registration, and one per changed field of a
+  /// modified registration.
+  pub fn write_csv(&self, writer: impl std::io::Write) -> Result<(), csv::Error> {
+    let mut writer = csv::Writer::from_writer(writer);
+
+    for details in &self.added {
+      writer.serialize(Row::whole("added", details))?;
+    }
+
+    for details in &self.removed {
+      writer.serialize(Row::whole("removed", details))?;
+    }
+
+    for modified in &self.modified {
+      for change in &modified.changes {
+        writer.serialize(Row {
+          field: Some(change.field),
+          old: change.old.as_deref(),
+          new: change.new.as_deref(),
+          ..Row::whole("modified", modified.new)
+        })?;
+      }
+    }
+
+    writer.flush()?;
+
+    Ok(())
+  }
+
+  pub fn write_json(&self, writer: impl std::io::Write) -> serde_json::Result<()> {
+    serde_json::to_writer_pretty(writer, self)
+  }
+}
+
+fn flat<S: Serializer>(details: &&Details, serializer: S) -> Result<S::Ok, S::Error> {
+  CsvDetailsRef(details, DateFormat::default()).serialize(serializer)
+}
+
+fn flat_all<S: Serializer>(details: &[&Details], serializer: S) -> Result<S::Ok, S::Error> {
+  serializer.collect_seq(
+    details
+      .iter()
+      .map(|details| CsvDetailsRef(details, DateFormat::default())),
+  )
+}
+
+#[test]
+fn diff() {
+  use crate::api::get::Parent;
+
+  let details = |id: u32, epithet: &str, registrant: &str| Details {
+    id,
+    genus: "Phalaenopsis".into(),
+    epithet: epithet.into(),
+    synonym: false,
+    synonym_details: None,
+    registrant_name: Some(registrant.into()),
+    originator_name: None,
+    date_of_registration: None,
+    seed_parent: None,
+    pollen_parent: None,
+  };
+
+  let mut old = Dump::empty();
+  old.insert(details(1, "Golden Peoker", "Brothers"));
+  old.insert(details(2, "Golden Sands", "Fields"));
+  old.insert(details(3, "Doris", "Duke Farms"));
+
+  let mut new = Dump::empty();
+  new.insert(details(1, "Golden Peoker", "Brothers"));
+  new.insert(Details {
+    seed_parent: Some(Parent {
+      genus: "Phalaenopsis".into(),
+      epithet: "Misty Green".into(),
+    }),
+    ..details(2, "Golden Sands", "Fields Orchids")
+  });
+  new.insert(details(4, "Sogo Yukidian", "Sogo"));
+
+  assert!(old.diff(&old).is_empty());
+
+  let diff = old.diff(&new);
+  assert_eq!(diff.added.iter().map(|d| d.id).collect::<Vec<_>>(), [4]);
+  assert_eq!(diff.removed.iter().map(|d| d.id).collect::<Vec<_>>(), [3]);
+  assert_eq!(diff.modified.len(), 1);
+  assert_eq!(
+    diff.modified[0].changes,
+    [
+      FieldChange {
+        field: "registrant_name",
+        old: Some("Fields".into()),
+        new: Some("Fields Orchids".into()),
+      },
+      FieldChange {
+        field: "seed_parent.genus",
+        old: None,
+        new: Some("Phalaenopsis".into()),
+      },
+      FieldChange {
+        field: "seed_parent.epithet",
+        old: None,
+        new: Some("Misty Green".into()),
+      },
+    ]
+  );
+
+  let mut csv = Vec::new();
+  diff.write_csv(&mut csv).unwrap();
+  let csv = String::from_utf8(csv).unwrap();
+  assert_eq!(
+    csv.lines().collect::<Vec<_>>(),
+    [
+      "change,id,genus,epithet,field,old,new",
+      "added,4,Phalaenopsis,Sogo Yukidian,,,",
+      "removed,3,Phalaenopsis,Doris,,,",
+      "modified,2,Phalaenopsis,Golden Sands,registrant_name,Fields,Fields Orchids",
+      "modified,2,Phalaenopsis,Golden Sands,seed_parent.genus,,Phalaenopsis",
+      "modified,2,Phalaenopsis,Golden Sands,seed_parent.epithet,,Misty Green",
+    ]
+  );
+
+  let mut json = Vec::new();
+  diff.write_json(&mut json).unwrap();
+  let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+  assert_eq!(json["added"][0]["epithet"], "Sogo Yukidian");
+  assert_eq!(json["modified"][0]["id"], 2);
+  assert_eq!(
+    json["modified"][0]["new"]["seed_parent.epithet"],
+    "Misty Green"
+  );
+  assert_eq!(
+    json["modified"][0]["changes"][0]["field"],
+    "registrant_name"
+  );
+}
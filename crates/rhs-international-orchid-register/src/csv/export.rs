@@ -0,0 +1,167 @@
+use std::{io::Write, sync::Arc};
+
+use icra_export::{
+  arrow_array::{ArrayRef, BooleanArray, Date32Array, StringArray, UInt32Array},
+  arrow_schema::{DataType, Field},
+  date32,
+  struct_array,
+  Error,
+};
+
+use crate::{
+  api::{Details, Parent},
+  csv::Dump,
+};
+
+impl Dump {
+  /// Writes each registration as a JSON object on its own line, ordered by id.
+  pub fn write_jsonl(&self, writer: impl Write) -> Result<(), Error> {
+    icra_export::write_jsonl(self.sorted(), writer)
+  }
+
+  /// Writes registrations to Parquet, ordered by id. Parents and synonyms are struct columns
+  /// shaped like their JSON objects.
+  pub fn write_parquet(&self, writer: impl Write + Send) -> Result<(), Error> {
+    let details = self.sorted();
+
+    let string = |value: &dyn Fn(&Details) -> Option<&str>| strings(&details, value);
+
+    let parent = |parent: fn(&Details) -> Option<&Parent>| {
+      struct_array(
+        vec![
+          (
+            Field::new("genus", DataType::Utf8, false),
+            string(&|d| Some(&parent(d)?.genus)),
+          ),
+          (
+            Field::new("epithet", DataType::Utf8, false),
+            string(&|d| Some(&parent(d)?.epithet)),
+          ),
+        ],
+        details.iter().map(|d| parent(d).is_some()),
+      )
+    };
+
+    let synonym_details = struct_array(
+      vec![
+        (
+          Field::new("genus", DataType::Utf8, false),
+          string(&|d| Some(&d.synonym_details.as_ref()?.genus)),
+        ),
+        (
+          Field::new("epithet", DataType::Utf8, true),
+          string(&|d| d.synonym_details.as_ref()?.epithet.as_deref()),
+        ),
+      ],
+      details.iter().map(|d| d.synonym_details.is_some()),
+    )?;
+    let seed_parent = parent(|d| d.seed_parent.as_ref())?;
+    let pollen_parent = parent(|d| d.pollen_parent.as_ref())?;
+
+    icra_export::write_parquet(
+      vec![
+        (
+          Field::new("id", DataType::UInt32, false),
+          Arc::new(UInt32Array::from_iter_values(details.iter().map(|d| d.id))),
+        ),
+        (
+          Field::new("genus", DataType::Utf8, false),
+          string(&|d| Some(&d.genus)),
+        ),
+        (
+          Field::new("epithet", DataType::Utf8, false),
+          string(&|d| Some(&d.epithet)),
+        ),
+        (
+          Field::new("synonym", DataType::Boolean, false),
+          Arc::new(BooleanArray::from_iter(
+            details.iter().map(|d| Some(d.synonym)),
+          )),
+        ),
+        (
+          Field::new("synonym_details", synonym_details.data_type().clone(), true),
+          synonym_details,
+        ),
+        (
+          Field::new("registrant_name", DataType::Utf8, true),
+          string(&|d| d.registrant_name.as_deref()),
+        ),
+        (
+          Field::new("originator_name", DataType::Utf8, true),
+          string(&|d| d.originator_name.as_deref()),
+        ),
+        (
+          Field::new("date_of_registration", DataType::Date32, true),
+          Arc::new(Date32Array::from_iter(
+            details.iter().map(|d| d.date_of_registration.map(date32)),
+          )),
+        ),
+        (
+          Field::new("seed_parent", seed_parent.data_type().clone(), true),
+          seed_parent,
+        ),
+        (
+          Field::new("pollen_parent", pollen_parent.data_type().clone(), true),
+          pollen_parent,
+        ),
+      ],
+      writer,
+    )
+  }
+}
+
+fn strings(details: &[&Details], value: impl Fn(&Details) -> Option<&str>) -> ArrayRef {
+  Arc::new(StringArray::from_iter(details.iter().map(|d| value(d))))
+}
+
+#[test]
+fn export() -> Result<(), Error> {
+  use time::{Date, Month};
+
+  let mut dump = Dump::empty();
+  dump.insert(Details {
+    id: 2,
+    genus: "Phalaenopsis".into(),
+    epithet: "Elisabethae".into(),
+    synonym: false,
+    synonym_details: None,
+    registrant_name: Some("Sander".into()),
+    originator_name: None,
+    date_of_registration: Some(Date::from_calendar_date(1927, Month::March, 4).unwrap()),
+    seed_parent: Some(Parent {
+      genus: "Phalaenopsis".into(),
+      epithet: "amabilis".into(),
+    }),
+    pollen_parent: None,
+  });
+  dump.insert(Details {
+    id: 1,
+    genus: "Phalaenopsis".into(),
+    epithet: "amabilis".into(),
+    synonym: false,
+    synonym_details: None,
+    registrant_name: None,
+    originator_name: None,
+    date_of_registration: None,
+    seed_parent: None,
+    pollen_parent: None,
+  });
+
+  let mut jsonl = Vec::new();
+  dump.write_jsonl(&mut jsonl)?;
+  let jsonl = String::from_utf8(jsonl).unwrap();
+  let lines = jsonl.lines().collect::<Vec<_>>();
+
+  assert_eq!(lines.len(), 2);
+  assert!(lines[0].starts_with("{\"id\":1,"));
+  assert!(
+    lines[1].contains("\"seed_parent\":{\"genus\":\"Phalaenopsis\",\"epithet\":\"amabilis\"}")
+  );
+  assert!(lines[1].contains("\"date_of_registration\":\"1927-03-04\""));
+
+  let mut parquet = Vec::new();
+  dump.write_parquet(&mut parquet)?;
+  assert!(parquet.starts_with(b"PAR1"));
+
+  Ok(())
+}
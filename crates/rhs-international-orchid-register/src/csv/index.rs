@@ -0,0 +1,323 @@
+use std::{
+  collections::{BTreeMap, HashMap},
+  ops::RangeInclusive,
+};
+
+use crate::{api::Details, csv::Dump};
+
+pub struct Index<'a> {
+  dump: &'a Dump,
+  ids: Vec<u32>,
+  genus: HashMap<String, Vec<u32>>,
+  epithet: Vec<(String, u32)>,
+  registrant: HashMap<String, Vec<u32>>,
+  originator: HashMap<String, Vec<u32>>,
+  year: BTreeMap<i32, Vec<u32>>,
+  terms: BTreeMap<String, Vec<u32>>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Sort {
+  #[default]
+  Id,
+  Name,
+  DateOfRegistration,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Query {
+  pub text: Option<String>,
+  pub genus: Option<String>,
+  pub epithet_prefix: Option<String>,
+  pub registrant: Option<String>,
+  pub originator: Option<String>,
+  pub years: Option<RangeInclusive<i32>>,
+  pub sort: Sort,
+  pub descending: bool,
+  pub offset: usize,
+  pub limit: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct Facets {
+  pub genus: BTreeMap<String, usize>,
+  pub year: BTreeMap<i32, usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Page<'a> {
+  pub total: usize,
+  pub results: Vec<&'a Details>,
+  pub facets: Facets,
+}
+
+fn key(value: &str) -> String {
+  value.trim().to_lowercase()
+}
+
+fn tokens(value: &str) -> impl Iterator<Item = String> + '_ {
+  value
+    .split(|c: char| !c.is_alphanumeric())
+    .filter(|t| !t.is_empty())
+    .map(|t| t.to_lowercase())
+}
+
+fn intersect(a: &[u32], b: &[u32]) -> Vec<u32> {
+  let mut res = Vec::new();
+  let (mut i, mut j) = (0, 0);
+
+  while i < a.len() && j < b.len() {
+    match a[i].cmp(&b[j]) {
+      std::cmp::Ordering::Less => i += 1,
+      std::cmp::Ordering::Greater => j += 1,
+      std::cmp::Ordering::Equal => {
+        res.push(a[i]);
+        i += 1;
+        j += 1;
+      },
+    }
+  }
+
+  res
+}
+
+fn union<'b>(lists: impl Iterator<Item = &'b Vec<u32>>) -> Vec<u32> {
+  let mut res = lists.flatten().copied().collect::<Vec<_>>();
+  res.sort_unstable();
+  res.dedup();
+  res
+}
+
+impl<'a> Index<'a> {
+  pub fn new(dump: &'a Dump) -> Self {
+    let mut ids = dump.data().keys().copied().collect::<Vec<_>>();
+    ids.sort_unstable();
+
+    let mut index = Self {
+      dump,
+      ids: Vec::with_capacity(ids.len()),
+      genus: HashMap::new(),
+      epithet: Vec::with_capacity(ids.len()),
+      registrant: HashMap::new(),
+      originator: HashMap::new(),
+      year: BTreeMap::new(),
+      terms: BTreeMap::new(),
+    };
+
+    // ids are visited in ascending order, so every posting list ends up sorted
+    for id in ids {
+      let details = &dump.data()[&id];
+
+      index.ids.push(id);
+      index.genus.entry(key(&details.genus)).or_default().push(id);
+      index.epithet.push((key(&details.epithet), id));
+
+      if let Some(name) = &details.registrant_name {
+        index.registrant.entry(key(name)).or_default().push(id);
+      }
+
+      if let Some(name) = &details.originator_name {
+        index.originator.entry(key(name)).or_default().push(id);
+      }
+
+      if let Some(date) = details.date_of_registration {
+        index.year.entry(date.year()).or_default().push(id);
+      }
+
+      let mut terms = [
+        Some(&details.genus),
+        Some(&details.epithet),
+        details.registrant_name.as_ref(),
+        details.originator_name.as_ref(),
+      ]
+      .into_iter()
+      .flatten()
+      .flat_map(|s| tokens(s))
+      .collect::<Vec<_>>();
+      terms.sort_unstable();
+      terms.dedup();
+
+      for term in terms {
+        index.terms.entry(term).or_default().push(id);
+      }
+    }
+
+    index.epithet.sort_unstable();
+
+    index
+  }
+
+  pub fn query(&self, query: &Query) -> Page<'a> {
+    let mut filters: Vec<Vec<u32>> = Vec::new();
+
+    let exact = |map: &HashMap<String, Vec<u32>>, value: &Option<String>| {
+      value
+        .as_ref()
+        .map(|value| map.get(&key(value)).cloned().unwrap_or_default())
+    };
+
+    filters.extend(exact(&self.genus, &query.genus));
+    filters.extend(exact(&self.registrant, &query.registrant));
+    filters.extend(exact(&self.originator, &query.originator));
+
+    if let Some(prefix) = &query.epithet_prefix {
+      let prefix = key(prefix);
+      let start = self
+        .epithet
+        .partition_point(|(e, _)| e.as_str() < prefix.as_str());
+      let mut ids = self.epithet[start..]
+        .iter()
+        .take_while(|(e, _)| e.starts_with(&prefix))
+        .map(|(_, id)| *id)
+        .collect::<Vec<_>>();
+      ids.sort_unstable();
+
+      filters.push(ids);
+    }
+
+    if let Some(years) = &query.years {
+      filters.push(union(self.year.range(years.clone()).map(|(_, ids)| ids)));
+    }
+
+    if let Some(text) = &query.text {
+      let tokens = tokens(text).collect::<Vec<_>>();
+
+      // text with nothing to search for, like punctuation, matches nothing rather than everything
+      if tokens.is_empty() {
+        filters.push(Vec::new());
+      }
+
+      for token in tokens {
+        let matches = self
+          .terms
+          .range(token.clone()..)
+          .take_while(|(term, _)| term.starts_with(&token))
+          .map(|(_, ids)| ids);
+
+        filters.push(union(matches));
+      }
+    }
+
+    filters.sort_by_key(|ids| ids.len());
+
+    let ids = match filters.split_first() {
+      Some((first, rest)) => rest
+        .iter()
+        .fold(first.clone(), |acc, ids| intersect(&acc, ids)),
+      None => self.ids.clone(),
+    };
+
+    let mut results = ids
+      .iter()
+      .filter_map(|id| self.dump.data().get(id))
+      .collect::<Vec<_>>();
+
+    let mut facets = Facets::default();
+    for details in &results {
+      *facets.genus.entry(details.genus.clone()).or_default() += 1;
+
+      if let Some(date) = details.date_of_registration {
+        *facets.year.entry(date.year()).or_default() += 1;
+      }
+    }
+
+    match query.sort {
+      Sort::Id => (),
+      Sort::Name => results.sort_by_cached_key(|d| (key(&d.genus), key(&d.epithet), d.id)),
+      Sort::DateOfRegistration => results.sort_by_key(|d| (d.date_of_registration, d.id)),
+    }
+
+    if query.descending {
+      results.reverse();
+    }
+
+    let total = results.len();
+    let results = results
+      .into_iter()
+      .skip(query.offset)
+      .take(query.limit.unwrap_or(usize::MAX))
+      .collect();
+
+    Page {
+      total,
+      results,
+      facets,
+    }
+  }
+}
+
+impl Dump {
+  pub fn index(&self) -> Index<'_> {
+    Index::new(self)
+  }
+}
+
+#[test]
+fn query() {
+  use time::{Date, Month};
+
+  let details = |id: u32, genus: &str, epithet: &str, registrant: &str, year: i32| Details {
+    id,
+    genus: genus.into(),
+    epithet: epithet.into(),
+    synonym: false,
+    synonym_details: None,
+    registrant_name: Some(registrant.into()),
+    originator_name: Some(registrant.into()),
+    date_of_registration: Some(Date::from_calendar_date(year, Month::March, 1).unwrap()),
+    seed_parent: None,
+    pollen_parent: None,
+  };
+
+  let mut dump = Dump::empty();
+  dump.insert(details(
+    1,
+    "Phalaenopsis",
+    "Golden Peoker",
+    "Brothers",
+    1983,
+  ));
+  dump.insert(details(2, "Phalaenopsis", "Golden Sands", "Fields", 1975));
+  dump.insert(details(3, "Cattleya", "Golden Slippers", "Brothers", 1990));
+  dump.insert(details(4, "Phalaenopsis", "Doris", "Duke Farms", 1940));
+
+  let index = dump.index();
+
+  let page = index.query(&Query {
+    genus: Some("phalaenopsis".into()),
+    epithet_prefix: Some("gold".into()),
+    ..Default::default()
+  });
+  assert_eq!(page.total, 2);
+  assert_eq!(page.facets.year, BTreeMap::from([(1975, 1), (1983, 1)]));
+
+  let page = index.query(&Query {
+    text: Some("golden bro".into()),
+    sort: Sort::DateOfRegistration,
+    descending: true,
+    ..Default::default()
+  });
+  let ids = page.results.iter().map(|d| d.id).collect::<Vec<_>>();
+  assert_eq!(ids, vec![3, 1]);
+
+  let page = index.query(&Query {
+    text: Some(" -- ".into()),
+    ..Default::default()
+  });
+  assert_eq!(page.total, 0);
+  assert!(page.facets.genus.is_empty());
+
+  let page = index.query(&Query {
+    years: Some(1970..=1999),
+    sort: Sort::Name,
+    offset: 1,
+    limit: Some(1),
+    ..Default::default()
+  });
+  assert_eq!(page.total, 3);
+  assert_eq!(page.results[0].id, 1);
+  assert_eq!(
+    page.facets.genus,
+    BTreeMap::from([("Cattleya".into(), 1), ("Phalaenopsis".into(), 2)])
+  );
+}
@@ -0,0 +1,211 @@
+//! An append-only log of changes to a [`Dump`], so a crawl can save its progress without
+//! rewriting the whole of `dump.csv` each time.
+
+use std::path::{Path, PathBuf};
+
+use tokio::io::AsyncWriteExt;
+
+use crate::{
+  api::Details,
+  csv::{csv::Error, Dump, Reason},
+};
+
+/// A change recorded in a [`Journal`].
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Entry {
+  /// A registration was fetched, so it's no longer known-bad.
+  Found(Box<Details>),
+  KnownBad(u32, Reason),
+}
+
+impl Dump {
+  pub fn apply(&mut self, entry: Entry) {
+    match entry {
+      Entry::Found(details) => {
+        self.remove_known_bad(details.id);
+        self.insert(*details);
+      },
+      Entry::KnownBad(id, reason) => self.insert_known_bad(id, reason),
+    }
+  }
+
+  /// Applies every entry in the journal at `path`, if there is one, returning how many there
+  /// were. A line cut short by a crash can only be the last one, and is ignored.
+  pub async fn replay(&mut self, path: impl AsRef<Path>) -> Result<usize, Error> {
+    let log = match tokio::fs::read(path).await {
+      Ok(log) => log,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+      Err(e) => Err(e)?,
+    };
+
+    let mut lines = log.split(|b| *b == b'\n').filter(|line| !line.is_empty());
+    let mut applied = 0;
+
+    while let Some(line) = lines.next() {
+      match serde_json::from_slice(line) {
+        Ok(entry) => self.apply(entry),
+        Err(_) if lines.next().is_none() => break,
+        Err(e) => Err(e)?,
+      }
+
+      applied += 1;
+    }
+
+    Ok(applied)
+  }
+}
+
+/// Appends changes to a log file, which is emptied when the changes are compacted into the
+/// dump's CSV files.
+#[derive(Debug)]
+pub struct Journal {
+  path: PathBuf,
+  file: tokio::fs::File,
+  appends: usize,
+}
+
+impl Journal {
+  /// Opens `path` for appending, creating it if needed. Entries already in it are kept, so
+  /// [`Dump::replay`] it first, but an entry cut short by a crash is removed.
+  pub async fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+    let path = path.as_ref().to_path_buf();
+
+    let file = tokio::fs::OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(&path)
+      .await?;
+
+    let log = tokio::fs::read(&path).await?;
+    if !log.is_empty() && !log.ends_with(b"\n") {
+      let end = log
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map_or(0, |end| end + 1);
+      file.set_len(end as u64).await?;
+    }
+
+    Ok(Self {
+      path,
+      file,
+      appends: 0,
+    })
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// How many times entries have been appended since the journal was opened or compacted.
+  pub fn appends(&self) -> usize {
+    self.appends
+  }
+
+  /// Writes `entries` to the end of the log and waits until they're on disk.
+  pub async fn append(&mut self, entries: &[Entry]) -> Result<(), Error> {
+    if entries.is_empty() {
+      return Ok(());
+    }
+
+    let mut buf = Vec::new();
+    for entry in entries {
+      serde_json::to_writer(&mut buf, entry)?;
+      buf.push(b'\n');
+    }
+
+    self.file.write_all(&buf).await?;
+    self.file.sync_data().await?;
+    self.appends += 1;
+
+    Ok(())
+  }
+
+  /// Writes `dump` to its CSV files and empties the log. If this is interrupted between the
+  /// two, replaying the log over the new files changes nothing.
+  pub async fn compact(
+    &mut self,
+    dump: &Dump,
+    data: impl AsRef<Path>,
+    known_bad: impl AsRef<Path>,
+  ) -> Result<(), Error> {
+    dump.write(data, known_bad).await?;
+
+    self.file.set_len(0).await?;
+    self.file.sync_all().await?;
+    self.appends = 0;
+
+    Ok(())
+  }
+}
+
+#[tokio::test]
+async fn recovery() -> Result<(), Error> {
+  let dir = tempfile::tempdir()?;
+  let dir = dir.path();
+  let log = dir.join("dump.log");
+
+  let details = |id| Details {
+    id,
+    genus: "Phalaenopsis".into(),
+    epithet: format!("Grex {id}"),
+    synonym: false,
+    synonym_details: None,
+    registrant_name: None,
+    originator_name: None,
+    date_of_registration: None,
+    seed_parent: None,
+    pollen_parent: None,
+  };
+
+  let mut dump = Dump::empty();
+  dump.insert_known_bad(2, Reason::Transient);
+
+  let mut journal = Journal::open(&log).await?;
+  journal
+    .append(&[
+      Entry::Found(Box::new(details(1))),
+      Entry::Found(Box::new(details(2))),
+    ])
+    .await?;
+  journal
+    .append(&[Entry::KnownBad(3, Reason::NotFound)])
+    .await?;
+  assert_eq!(journal.appends(), 2);
+
+  // a crash part-way through writing an entry
+  let mut file = tokio::fs::OpenOptions::new()
+    .append(true)
+    .open(&log)
+    .await?;
+  file.write_all(b"{\"found\":{\"id\":4,").await?;
+
+  let mut recovered = dump.clone();
+  assert_eq!(recovered.replay(&log).await?, 3);
+
+  let mut journal = Journal::open(&log).await?;
+  journal
+    .append(&[Entry::KnownBad(5, Reason::NotFound)])
+    .await?;
+
+  let mut recovered = dump.clone();
+  assert_eq!(recovered.replay(&log).await?, 4);
+  assert!(recovered.is_known_bad(crate::api::Get { id: 5 }));
+  assert!(recovered.has(crate::api::Get { id: 2 }));
+  assert!(!recovered.is_transient(crate::api::Get { id: 2 }));
+  assert!(recovered.is_known_bad(crate::api::Get { id: 3 }));
+  assert!(!recovered.has(crate::api::Get { id: 4 }));
+
+  journal
+    .compact(&recovered, dir.join("dump.csv"), dir.join("known_bad.csv"))
+    .await?;
+  assert_eq!(tokio::fs::read(&log).await?.len(), 0);
+  assert_eq!(journal.appends(), 0);
+
+  let reloaded =
+    Dump::from_data_and_known_bad_files(dir.join("dump.csv"), dir.join("known_bad.csv")).await?;
+  assert_eq!(reloaded.data().len(), 2);
+  assert!(reloaded.is_known_bad(crate::api::Get { id: 3 }));
+
+  Ok(())
+}
@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{collections::BTreeMap, fmt::Display, num::ParseIntError, str::FromStr};
 
 use serde::de::Visitor;
 
@@ -8,7 +8,65 @@ pub enum KnownBad {
   Range(std::ops::RangeInclusive<u32>),
 }
 
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  Hash,
+  serde::Serialize,
+  serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Reason {
+  /// The register returned a page without an entry for this id.
+  #[default]
+  NotFound,
+  /// The lookup kept failing (timeouts, 5xx, ...), so the id should be tried again later.
+  Transient,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseKnownBadError {
+  #[error("invalid id {0:?}: {1}")]
+  Id(String, ParseIntError),
+  #[error("range {0}..={1} ends before it starts")]
+  Reversed(u32, u32),
+  #[error("expected `N` or `A..=B`, found {0:?}")]
+  Format(String),
+}
+
+/// A row of `known_bad.csv`, where the reason column is optional for older files.
+#[derive(Debug, Clone, serde::Deserialize)]
+struct Record(KnownBad, #[serde(default)] Reason);
+
+/// Reads `known_bad.csv` rows, skipping blank lines and `#` comments.
+pub fn read_known_bad(
+  reader: impl std::io::Read,
+) -> impl Iterator<Item = Result<(KnownBad, Reason), csv::Error>> {
+  csv::ReaderBuilder::new()
+    .has_headers(false)
+    .flexible(true)
+    .comment(Some(b'#'))
+    .trim(csv::Trim::All)
+    .from_reader(reader)
+    .into_deserialize()
+    .map(|record| record.map(|Record(known_bad, reason)| (known_bad, reason)))
+}
+
 impl KnownBad {
+  pub fn new(start: u32, end: u32) -> Self {
+    if start == end {
+      KnownBad::Single(start)
+    } else {
+      KnownBad::Range(start..=end)
+    }
+  }
+
   pub fn contains(&self, value: u32) -> bool {
     match self {
       KnownBad::Single(i) => *i == value,
@@ -16,6 +74,13 @@ impl KnownBad {
     }
   }
 
+  pub fn ids(&self) -> std::ops::RangeInclusive<u32> {
+    match self {
+      KnownBad::Single(i) => *i..=*i,
+      KnownBad::Range(i) => i.clone(),
+    }
+  }
+
   fn start(&self) -> u32 {
     match self {
       KnownBad::Single(i) => *i,
@@ -24,6 +89,106 @@ impl KnownBad {
   }
 }
 
+/// Sorted, non-overlapping and non-adjacent id ranges, keyed by their start.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KnownBadSet {
+  ranges: BTreeMap<u32, u32>,
+}
+
+impl KnownBadSet {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn contains(&self, id: u32) -> bool {
+    self
+      .ranges
+      .range(..=id)
+      .next_back()
+      .is_some_and(|(_, end)| *end >= id)
+  }
+
+  pub fn insert(&mut self, id: u32) {
+    self.insert_range(id, id);
+  }
+
+  pub fn insert_range(&mut self, start: u32, end: u32) {
+    let (mut start, mut end) = (start.min(end), start.max(end));
+
+    if let Some((&prev_start, &prev_end)) = self.ranges.range(..=start).next_back() {
+      if prev_end >= start.saturating_sub(1) {
+        start = prev_start;
+        end = end.max(prev_end);
+      }
+    }
+
+    let merged = self
+      .ranges
+      .range(start..=end.saturating_add(1))
+      .map(|(start, end)| (*start, *end))
+      .collect::<Vec<_>>();
+
+    for (next_start, next_end) in merged {
+      self.ranges.remove(&next_start);
+      end = end.max(next_end);
+    }
+
+    self.ranges.insert(start, end);
+  }
+
+  pub fn remove(&mut self, id: u32) {
+    let Some((&start, &end)) = self.ranges.range(..=id).next_back() else {
+      return;
+    };
+
+    if end < id {
+      return;
+    }
+
+    self.ranges.remove(&start);
+
+    if start < id {
+      self.ranges.insert(start, id - 1);
+    }
+
+    if id < end {
+      self.ranges.insert(id + 1, end);
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.ranges.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.ranges.is_empty()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = KnownBad> + '_ {
+    self
+      .ranges
+      .iter()
+      .map(|(start, end)| KnownBad::new(*start, *end))
+  }
+}
+
+impl Extend<KnownBad> for KnownBadSet {
+  fn extend<T: IntoIterator<Item = KnownBad>>(&mut self, iter: T) {
+    for known_bad in iter {
+      let ids = known_bad.ids();
+      self.insert_range(*ids.start(), *ids.end());
+    }
+  }
+}
+
+impl FromIterator<KnownBad> for KnownBadSet {
+  fn from_iter<T: IntoIterator<Item = KnownBad>>(iter: T) -> Self {
+    let mut set = Self::new();
+    set.extend(iter);
+    set
+  }
+}
+
 impl PartialOrd for KnownBad {
   fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
     Some(self.cmp(other))
@@ -56,7 +221,7 @@ impl<'de> serde::Deserialize<'de> for KnownBad {
       type Value = KnownBad;
 
       fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        formatter.write_str("enum KnownBad")
+        formatter.write_str("an id `N` or an id range `A..=B`")
       }
 
       fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
@@ -70,15 +235,7 @@ impl<'de> serde::Deserialize<'de> for KnownBad {
       where
         E: serde::de::Error,
       {
-        let split = v.split("..=").collect::<Vec<_>>();
-
-        let res = match split.len() {
-          1 => KnownBad::Single(split[0].parse().unwrap()),
-          2 => KnownBad::Range(split[0].parse().unwrap()..=split[1].parse().unwrap()),
-          i => Err(E::invalid_length(i, &self))?,
-        };
-
-        Ok(res)
+        v.parse().map_err(E::custom)
       }
     }
 
@@ -86,6 +243,34 @@ impl<'de> serde::Deserialize<'de> for KnownBad {
   }
 }
 
+impl FromStr for KnownBad {
+  type Err = ParseKnownBadError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let id = |s: &str| {
+      let s = s.trim();
+      s.parse::<u32>()
+        .map_err(|e| ParseKnownBadError::Id(s.to_string(), e))
+    };
+
+    let split = s.split("..=").collect::<Vec<_>>();
+
+    match split[..] {
+      [single] => Ok(KnownBad::Single(id(single)?)),
+      [start, end] => {
+        let (start, end) = (id(start)?, id(end)?);
+
+        if end < start {
+          Err(ParseKnownBadError::Reversed(start, end))
+        } else {
+          Ok(KnownBad::Range(start..=end))
+        }
+      },
+      _ => Err(ParseKnownBadError::Format(s.to_string())),
+    }
+  }
+}
+
 impl Display for KnownBad {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
@@ -94,3 +279,78 @@ impl Display for KnownBad {
     }
   }
 }
+
+#[test]
+fn known_bad_set() {
+  let mut set = KnownBadSet::new();
+
+  set.insert(5);
+  set.insert(7);
+  set.insert_range(10, 12);
+  assert_eq!(set.len(), 3);
+
+  set.insert(6);
+  set.insert(9);
+  assert_eq!(
+    set.iter().collect::<Vec<_>>(),
+    vec![KnownBad::Range(5..=7), KnownBad::Range(9..=12)]
+  );
+
+  set.insert(8);
+  assert_eq!(
+    set.iter().collect::<Vec<_>>(),
+    vec![KnownBad::Range(5..=12)]
+  );
+
+  set.insert_range(0, 3);
+  set.insert_range(2, 4);
+  assert_eq!(
+    set.iter().collect::<Vec<_>>(),
+    vec![KnownBad::Range(0..=12)]
+  );
+
+  set.remove(0);
+  set.remove(6);
+  assert!(!set.contains(6));
+  assert!(set.contains(7));
+  assert_eq!(
+    set.iter().map(|k| k.to_string()).collect::<Vec<_>>(),
+    vec!["1..=5", "7..=12"]
+  );
+
+  set.insert(u32::MAX);
+  set.insert(u32::MAX - 1);
+  assert!(set.contains(u32::MAX));
+  assert_eq!(set.len(), 3);
+}
+
+#[test]
+fn parse() {
+  assert_eq!("815".parse(), Ok(KnownBad::Single(815)));
+  assert_eq!(" 0..=815 ".parse(), Ok(KnownBad::Range(0..=815)));
+  assert_eq!(
+    "5..=1".parse::<KnownBad>(),
+    Err(ParseKnownBadError::Reversed(5, 1))
+  );
+  assert!(matches!(
+    "id".parse::<KnownBad>(),
+    Err(ParseKnownBadError::Id(..))
+  ));
+  assert!(matches!(
+    "1..=2..=3".parse::<KnownBad>(),
+    Err(ParseKnownBadError::Format(..))
+  ));
+
+  let csv = "# ids below 816 were never assigned\n0..=815\n\n 861 \n900,transient\n";
+  let records = read_known_bad(csv.as_bytes())
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+  assert_eq!(records.len(), 3);
+  assert_eq!(records[2], (KnownBad::Single(900), Reason::Transient));
+
+  let err = read_known_bad("10..=2\n".as_bytes())
+    .next()
+    .unwrap()
+    .unwrap_err();
+  assert!(err.to_string().contains("10..=2 ends before it starts"));
+}
@@ -1,9 +1,38 @@
 #[allow(clippy::module_inception)]
 mod csv;
+mod diff;
+#[cfg(feature = "export")]
+mod export;
+mod index;
+mod journal;
 mod known_bad;
 mod patch;
+mod pedigree;
+mod progeny;
+mod search;
 mod serde;
+mod stream;
+mod validate;
 
 pub use csv::{Dump, Error as DumpError};
-pub use known_bad::KnownBad;
-pub use patch::Patches;
+pub use diff::{Diff, FieldChange, Modified};
+pub use index::{Facets, Index, Page, Query, Sort};
+pub use journal::{Entry as JournalEntry, Journal};
+pub use known_bad::{read_known_bad, KnownBad, KnownBadSet, ParseKnownBadError, Reason};
+pub use patch::{
+  Change as PatchChange,
+  Error as PatchError,
+  Outcome as PatchOutcome,
+  Patch,
+  Patches,
+  Report as PatchReport,
+  Status as PatchStatus,
+};
+pub use pedigree::Pedigree;
+pub use progeny::{Descendant, Progeny, ProgenyIndex};
+#[cfg(feature = "postgres")]
+pub(crate) use search::Pattern;
+pub use stream::{DumpReader, DumpWriter};
+pub use validate::{Issue, IssueCode, ParseIssueCodeError, Validation};
+
+pub use self::serde::DateFormat;
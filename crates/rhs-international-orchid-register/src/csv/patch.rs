@@ -1,42 +1,676 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+  collections::{BTreeMap, HashMap},
+  path::Path,
+  str::FromStr,
+};
 
-use crate::{api::Details, csv::serde::CsvDetails};
+use crate::{
+  api::{
+    get::{Parent, Synonym},
+    Details,
+  },
+  csv::{
+    serde::{fields, CsvDetails, DateFormat},
+    IssueCode,
+    ParseIssueCodeError,
+  },
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
   #[error("This entry does not exist")]
   None,
+  #[cfg(feature = "scrape")]
   #[error(transparent)]
   Get(#[from] crate::api::get::Error),
   #[error(transparent)]
   Io(#[from] std::io::Error),
   #[error(transparent)]
   Csv(#[from] csv::Error),
+  #[error("unknown action {0:?}, expected `set`, `delete` or `suppress`")]
+  Action(String),
+  #[error(transparent)]
+  Code(#[from] ParseIssueCodeError),
+  #[error("unknown field {0:?}")]
+  Field(String),
+  #[error("invalid value {1:?} for {0}: {2}")]
+  Value(String, String, String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+  /// Overrides one column, where `field` is a column name of the dump CSV.
+  Set {
+    field: String,
+    value: Option<String>,
+    /// What the register had when the patch was written, if recorded.
+    upstream: Option<String>,
+  },
+  /// Replaces the whole entry, as patch files with the dump's columns do.
+  Record(Box<Details>),
+  /// Drops the entry entirely.
+  Delete,
+  /// Hides a validation issue for this id, or only the one in `field`.
+  Suppress {
+    code: IssueCode,
+    field: Option<String>,
+  },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+  pub id: u32,
+  pub change: Change,
+  pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+  Applied,
+  /// The data already had the patched value, so the patch can probably be removed.
+  AlreadyMatches,
+  /// The register no longer has the value the patch was written against. The patch is still
+  /// applied, but should be checked against the upstream correction.
+  UpstreamChanged {
+    found: Option<String>,
+  },
+  /// Fields were set on an id with no entry, and not enough to create one.
+  Missing,
+}
+
+#[derive(Debug, Clone)]
+pub struct Outcome<'a> {
+  pub patch: &'a Patch,
+  pub status: Status,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Report<'a> {
+  pub outcomes: Vec<Outcome<'a>>,
+}
+
+impl<'a> Report<'a> {
+  /// Patches that are redundant or were written against data that has since changed.
+  pub fn needs_review(&self) -> impl Iterator<Item = &Outcome<'a>> {
+    self
+      .outcomes
+      .iter()
+      .filter(|outcome| outcome.status != Status::Applied)
+  }
 }
 
+#[derive(Debug, Clone, Default)]
 pub struct Patches {
-  data: HashMap<u32, Details>,
+  patches: Vec<Patch>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Row {
+  id: u32,
+  action: String,
+  field: Option<String>,
+  value: Option<String>,
+  upstream: Option<String>,
+  reason: Option<String>,
 }
 
 impl Patches {
   pub async fn from_data_file(path: impl AsRef<Path>) -> Result<Self, Error> {
     let path = path.as_ref();
-    let file = tokio::fs::read(path).await?;
-    let data: Result<Vec<CsvDetails>, _> = csv::ReaderBuilder::new()
-      .comment(Some(b'#'))
-      .from_reader(&file[..])
-      .deserialize()
+    let file = tokio::fs::read_to_string(path).await?;
+
+    file.parse()
+  }
+
+  pub fn write(&self, writer: impl std::io::Write) -> Result<(), csv::Error> {
+    let mut writer = csv::Writer::from_writer(writer);
+
+    for patch in &self.patches {
+      let rows = match &patch.change {
+        Change::Set {
+          field,
+          value,
+          upstream,
+        } => vec![("set", Some(field.clone()), value.clone(), upstream.clone())],
+        // written as a `set` for every column
+        Change::Record(details) => fields(details)
+          .into_iter()
+          .map(|(field, value)| ("set", Some(field.to_string()), value, None))
+          .collect(),
+        Change::Delete => vec![("delete", None, None, None)],
+        Change::Suppress { code, field } => {
+          vec![("suppress", field.clone(), Some(code.to_string()), None)]
+        },
+      };
+
+      for (action, field, value, upstream) in rows {
+        writer.serialize(Row {
+          id: patch.id,
+          action: action.to_string(),
+          field,
+          value,
+          upstream,
+          reason: patch.reason.clone(),
+        })?;
+      }
+    }
+
+    writer.flush()?;
+
+    Ok(())
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Patch> {
+    self.patches.iter()
+  }
+
+  /// Drops overrides that `upstream` already agrees with and records the upstream value of the
+  /// rest, which turns whole-record patches into field-level ones.
+  pub fn minimise(&self, upstream: &HashMap<u32, Details>) -> Self {
+    let patches = self
+      .patches
+      .iter()
+      .flat_map(|patch| {
+        let Some(details) = upstream.get(&patch.id) else {
+          return vec![patch.clone()];
+        };
+
+        let sets = match &patch.change {
+          Change::Set { field, value, .. } => vec![(field.clone(), value.clone())],
+          Change::Record(record) => fields(record)
+            .into_iter()
+            .map(|(field, value)| (field.to_string(), value))
+            .collect(),
+          _ => return vec![patch.clone()],
+        };
+
+        sets
+          .into_iter()
+          .filter_map(|(field, value)| {
+            let found = field_value(details, &field);
+
+            (found != value).then(|| Patch {
+              change: Change::Set {
+                field,
+                value,
+                upstream: found,
+              },
+              ..patch.clone()
+            })
+          })
+          .collect()
+      })
       .collect();
-    let data = data?;
 
-    let data = HashMap::from_iter(data.into_iter().map(|i| (i.0.id, i.0)));
+    Self { patches }
+  }
+
+  pub fn apply_all(&self, details: &mut HashMap<u32, Details>) -> Report<'_> {
+    let mut report = Report::default();
+    let mut by_id = BTreeMap::<u32, Vec<&Patch>>::new();
+
+    for patch in &self.patches {
+      by_id.entry(patch.id).or_default().push(patch);
+    }
+
+    for (id, patches) in by_id {
+      let existed = details.contains_key(&id);
+      let mut entry = details.remove(&id).unwrap_or_else(|| empty(id));
+      let mut deleted = false;
+      let start = report.outcomes.len();
+      let sets = patches
+        .iter()
+        .any(|patch| matches!(patch.change, Change::Set { .. } | Change::Record(_)));
+
+      for patch in patches {
+        let status = match &patch.change {
+          Change::Delete if existed && !deleted => Status::Applied,
+          Change::Delete => Status::AlreadyMatches,
+          Change::Suppress { .. } => Status::Applied,
+          Change::Record(record) => {
+            let matches = existed && fields(&entry) == fields(record);
+            entry = Details {
+              id,
+              ..(**record).clone()
+            };
+
+            if matches {
+              Status::AlreadyMatches
+            } else {
+              Status::Applied
+            }
+          },
+          Change::Set {
+            field,
+            value,
+            upstream,
+          } => {
+            let found = field_value(&entry, field);
+
+            // fields that fail to parse were rejected when the patches were read
+            let _ = set_field(&mut entry, field, value.as_deref());
 
-    Ok(Self { data })
+            if !existed {
+              Status::Applied
+            } else if found == *value {
+              Status::AlreadyMatches
+            } else if upstream.is_some() && found != *upstream {
+              Status::UpstreamChanged { found }
+            } else {
+              Status::Applied
+            }
+          },
+        };
+
+        deleted |= patch.change == Change::Delete;
+        report.outcomes.push(Outcome { patch, status });
+      }
+
+      if deleted || (!existed && !sets) {
+        continue;
+      }
+
+      if !existed && (entry.genus.is_empty() || entry.epithet.is_empty()) {
+        for outcome in &mut report.outcomes[start..] {
+          outcome.status = Status::Missing;
+        }
+
+        continue;
+      }
+
+      details.insert(id, entry);
+    }
+
+    report
   }
+}
+
+/// Reads patches of `id,action,field,value,upstream,reason` rows.
+///
+/// Files with the dump's columns instead are read as whole-record patches, replacing every
+/// column. In both, a `#` comment is the reason for the rows below it up to the next blank
+/// line, unless a row has its own.
+impl FromStr for Patches {
+  type Err = Error;
+
+  fn from_str(data: &str) -> Result<Self, Self::Err> {
+    // split into runs of rows, each under the comment above it
+    let mut blocks: Vec<(Option<String>, String)> = Vec::new();
+    let mut reason: Option<String> = None;
+    let mut in_comment = false;
+    let mut in_block = false;
+
+    for line in data.lines() {
+      let text = line.trim();
+
+      if let Some(comment) = text.strip_prefix('#') {
+        let comment = comment.trim();
+        reason = match reason.filter(|_| in_comment) {
+          Some(reason) => Some(format!("{reason} {comment}")),
+          None => Some(comment.to_string()),
+        };
+        (in_comment, in_block) = (true, false);
+      } else if text.is_empty() {
+        (reason, in_comment, in_block) = (None, false, false);
+      } else {
+        if !in_block {
+          blocks.push((reason.clone(), String::new()));
+        }
 
-  pub fn apply_all(&self, details: &mut HashMap<u32, Details>) {
-    for patch in self.data.values() {
-      details.insert(patch.id, patch.clone());
+        let (_, rows) = blocks.last_mut().expect("a block was just pushed");
+        rows.push_str(line);
+        rows.push('\n');
+        (in_comment, in_block) = (false, true);
+      }
     }
+
+    let mut blocks = blocks.into_iter();
+    let Some((header_reason, header)) = blocks.next() else {
+      return Ok(Self::default());
+    };
+
+    let mut reader = csv::ReaderBuilder::new().from_reader(std::io::Cursor::new(header));
+    let headers = reader.headers()?.clone();
+    let legacy = !headers.iter().any(|header| header == "action");
+
+    // rows straight after the header share its block
+    let records = std::iter::once((header_reason, reader))
+      .chain(blocks.map(|(reason, rows)| {
+        let reader = csv::ReaderBuilder::new()
+          .has_headers(false)
+          .from_reader(std::io::Cursor::new(rows));
+        (reason, reader)
+      }))
+      .flat_map(|(reason, reader)| {
+        reader
+          .into_records()
+          .map(move |record| (reason.clone(), record))
+      });
+
+    let mut patches = Vec::new();
+
+    for (comment, record) in records {
+      let record = record?;
+
+      if legacy {
+        let CsvDetails(details) = record.deserialize(Some(&headers))?;
+
+        patches.push(Patch {
+          id: details.id,
+          change: Change::Record(Box::new(details)),
+          reason: comment,
+        });
+
+        continue;
+      }
+
+      let row: Row = record.deserialize(Some(&headers))?;
+
+      let change = match row.action.trim() {
+        "set" => {
+          let field = row.field.unwrap_or_default();
+
+          Change::Set {
+            value: normalise(&field, row.value.as_deref())?,
+            upstream: row
+              .upstream
+              .map(|upstream| normalise(&field, Some(&upstream)))
+              .transpose()?
+              .flatten(),
+            field,
+          }
+        },
+        "delete" => Change::Delete,
+        "suppress" => Change::Suppress {
+          code: row.value.unwrap_or_default().parse()?,
+          field: row.field,
+        },
+        action => return Err(Error::Action(action.to_string())),
+      };
+
+      patches.push(Patch {
+        id: row.id,
+        change,
+        reason: row.reason.or(comment),
+      });
+    }
+
+    Ok(Self { patches })
+  }
+}
+
+fn empty(id: u32) -> Details {
+  Details {
+    id,
+    genus: String::new(),
+    epithet: String::new(),
+    synonym: false,
+    synonym_details: None,
+    registrant_name: None,
+    originator_name: None,
+    date_of_registration: None,
+    seed_parent: None,
+    pollen_parent: None,
+  }
+}
+
+fn field_value(details: &Details, field: &str) -> Option<String> {
+  fields(details)
+    .into_iter()
+    .find(|(name, _)| *name == field)
+    .and_then(|(_, value)| value)
+}
+
+/// Rewrites `value` the way the dump writes `field`, so dates in either format compare equal.
+fn normalise(field: &str, value: Option<&str>) -> Result<Option<String>, Error> {
+  let mut details = empty(0);
+  set_field(&mut details, field, value)?;
+
+  Ok(field_value(&details, field))
+}
+
+fn set_field(details: &mut Details, field: &str, value: Option<&str>) -> Result<(), Error> {
+  let value = value.map(str::trim).filter(|v| !v.is_empty());
+  let invalid = |value: &str, e: &dyn std::fmt::Display| {
+    Error::Value(field.to_string(), value.to_string(), e.to_string())
+  };
+
+  let parent_genus = |parent: &mut Option<Parent>| match value {
+    None => *parent = None,
+    Some(genus) => {
+      parent
+        .get_or_insert_with(|| Parent {
+          genus: String::new(),
+          epithet: String::new(),
+        })
+        .genus = genus.to_string()
+    },
+  };
+
+  let parent_epithet = |parent: &mut Option<Parent>| match (parent.as_mut(), value) {
+    (Some(parent), epithet) => parent.epithet = epithet.unwrap_or_default().to_string(),
+    (None, Some(epithet)) => {
+      *parent = Some(Parent {
+        genus: String::new(),
+        epithet: epithet.to_string(),
+      })
+    },
+    (None, None) => (),
+  };
+
+  match field {
+    "genus" => details.genus = value.unwrap_or_default().to_string(),
+    "epithet" => details.epithet = value.unwrap_or_default().to_string(),
+    "synonym" => {
+      details.synonym = match value {
+        None => false,
+        Some(value) => value.parse().map_err(|e| invalid(value, &e))?,
+      }
+    },
+    "synonym_details.genus" => match value {
+      None => details.synonym_details = None,
+      Some(genus) => {
+        details
+          .synonym_details
+          .get_or_insert_with(|| Synonym {
+            genus: String::new(),
+            epithet: None,
+          })
+          .genus = genus.to_string()
+      },
+    },
+    "synonym_details.epithet" => match (&mut details.synonym_details, value) {
+      (Some(synonym), epithet) => synonym.epithet = epithet.map(str::to_string),
+      (synonym, epithet) => {
+        *synonym = epithet.map(|epithet| Synonym {
+          genus: String::new(),
+          epithet: Some(epithet.to_string()),
+        })
+      },
+    },
+    "registrant_name" => details.registrant_name = value.map(str::to_string),
+    "originator_name" => details.originator_name = value.map(str::to_string),
+    "date_of_registration" => {
+      details.date_of_registration = match value {
+        None => None,
+        Some(value) => Some(DateFormat::parse(value).map_err(|e| invalid(value, &e))?),
+      }
+    },
+    "seed_parent.genus" => parent_genus(&mut details.seed_parent),
+    "seed_parent.epithet" => parent_epithet(&mut details.seed_parent),
+    "pollen_parent.genus" => parent_genus(&mut details.pollen_parent),
+    "pollen_parent.epithet" => parent_epithet(&mut details.pollen_parent),
+    _ => return Err(Error::Field(field.to_string())),
   }
+
+  Ok(())
+}
+
+#[test]
+fn field_patches() -> Result<(), Error> {
+  let upstream = |id: u32, epithet: &str, registrant: &str| Details {
+    id,
+    genus: "Phalaenopsis".into(),
+    epithet: epithet.into(),
+    registrant_name: Some(registrant.into()),
+    ..empty(id)
+  };
+
+  let mut details = HashMap::from_iter(
+    [
+      upstream(1, "Golden Peoker", "Brothers"),
+      upstream(2, "Golden Sands", "Fields"),
+      upstream(3, "Doris", "Duke Farms"),
+    ]
+    .map(|details| (details.id, details)),
+  );
+
+  let patches: Patches = "id,action,field,value,upstream,reason
+# misspelt upstream
+1,set,registrant_name,Brothers Orchids,Brother,
+1,set,seed_parent.genus,Phalaenopsis,,
+1,set,seed_parent.epithet,Misty Green,,
+
+2,set,date_of_registration,1975/06/01,,registered before the register's earliest date
+2,set,epithet,Golden Sands,,
+3,delete,,,,duplicate of 2
+4,set,registrant_name,Nobody,,
+"
+  .parse()?;
+
+  assert_eq!(
+    patches.iter().next().unwrap().reason.as_deref(),
+    Some("misspelt upstream")
+  );
+  assert_eq!(
+    patches.iter().nth(2).unwrap().reason.as_deref(),
+    Some("misspelt upstream")
+  );
+  assert_eq!(patches.iter().nth(4).unwrap().reason, None);
+
+  let report = patches.apply_all(&mut details);
+  let statuses = report
+    .outcomes
+    .iter()
+    .map(|outcome| (outcome.patch.id, outcome.status.clone()))
+    .collect::<Vec<_>>();
+
+  assert_eq!(
+    statuses,
+    [
+      (
+        1,
+        Status::UpstreamChanged {
+          found: Some("Brothers".into())
+        }
+      ),
+      (1, Status::Applied),
+      (1, Status::Applied),
+      (2, Status::Applied),
+      (2, Status::AlreadyMatches),
+      (3, Status::Applied),
+      (4, Status::Missing),
+    ]
+  );
+  assert_eq!(report.needs_review().count(), 3);
+
+  assert_eq!(details.len(), 2);
+  assert_eq!(
+    details[&1].registrant_name.as_deref(),
+    Some("Brothers Orchids")
+  );
+  assert_eq!(
+    details[&1].seed_parent.as_ref().unwrap().epithet,
+    "Misty Green"
+  );
+  assert_eq!(details[&2].registrant_name.as_deref(), Some("Fields"));
+
+  let mut written = Vec::new();
+  patches.write(&mut written)?;
+  let reread: Patches = String::from_utf8(written).unwrap().parse()?;
+  assert_eq!(reread.patches, patches.patches);
+
+  assert!(matches!(
+    "id,action,field,value,upstream,reason\n1,set,colour,red,,\n".parse::<Patches>(),
+    Err(Error::Field(_))
+  ));
+  assert!(matches!(
+    "id,action,field,value,upstream,reason\n1,set,date_of_registration,soon,,\n".parse::<Patches>(),
+    Err(Error::Value(..))
+  ));
+
+  Ok(())
+}
+
+#[test]
+fn whole_record_patches() -> Result<(), Error> {
+  let patches: Patches = std::fs::read_to_string("data/patches.csv")?.parse()?;
+
+  let patch = patches.iter().find(|patch| patch.id == 920551).unwrap();
+  assert_eq!(
+    patch.reason.as_deref(),
+    Some("Listed as synonyms of each other")
+  );
+
+  // a whole record already in the data is one redundant patch, not one per column
+  let Change::Record(record) = &patch.change else {
+    panic!("expected a whole-record patch");
+  };
+  let mut details = HashMap::from([(patch.id, (**record).clone())]);
+  let report = patches.apply_all(&mut details);
+  let statuses = report
+    .outcomes
+    .iter()
+    .filter(|outcome| outcome.patch.id == 920551)
+    .map(|outcome| outcome.status.clone())
+    .collect::<Vec<_>>();
+  assert_eq!(statuses, [Status::AlreadyMatches]);
+  assert_eq!(report.outcomes.len(), patches.iter().count());
+
+  let legacy: Patches = "# checked against the printed register
+id,genus,epithet,synonym,synonym_details.genus,synonym_details.epithet,registrant_name,originator_name,date_of_registration,seed_parent.genus,seed_parent.epithet,pollen_parent.genus,pollen_parent.epithet
+1,Phalaenopsis,amabilis,false,,,,,,,,,
+"
+  .parse()?;
+  assert_eq!(
+    legacy.iter().next().unwrap().reason.as_deref(),
+    Some("checked against the printed register")
+  );
+
+  let mut upstream = HashMap::new();
+  let mut entry = empty(920551);
+  set_field(&mut entry, "genus", Some("Gomesa"))?;
+  set_field(&mut entry, "epithet", Some("cruciata"))?;
+  set_field(&mut entry, "synonym", Some("true"))?;
+  upstream.insert(entry.id, entry);
+
+  let minimised = patches.minimise(&upstream);
+  let changes = minimised
+    .iter()
+    .filter(|patch| patch.id == 920551)
+    .map(|patch| &patch.change)
+    .collect::<Vec<_>>();
+
+  assert_eq!(
+    changes,
+    [
+      &Change::Set {
+        field: "synonym".into(),
+        value: Some("false".into()),
+        upstream: Some("true".into()),
+      },
+      &Change::Set {
+        field: "synonym_details.genus".into(),
+        value: Some("Oncidium".into()),
+        upstream: None,
+      },
+      &Change::Set {
+        field: "synonym_details.epithet".into(),
+        value: Some("cruciatum".into()),
+        upstream: None,
+      },
+    ]
+  );
+
+  Ok(())
 }
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use crate::{
+  api::{get::Parent, Details, Get},
+  csv::Dump,
+};
+
+/// A registration and, recursively, the registrations of its parents. A parent that's named but
+/// isn't registered, or is beyond the requested number of generations, is left out.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Pedigree {
+  pub details: Details,
+  pub seed_parent: Option<Box<Pedigree>>,
+  pub pollen_parent: Option<Box<Pedigree>>,
+}
+
+impl Pedigree {
+  /// Builds a pedigree from `root`, finding parents with `lookup` and stopping after
+  /// `max_generation` generations of ancestors, or at any registration that's its own ancestor.
+  pub fn build<'a>(
+    root: &'a Details,
+    max_generation: Option<u32>,
+    lookup: &impl Fn(&Parent) -> Option<&'a Details>,
+  ) -> Self {
+    build(root, 0, max_generation, lookup, &mut Vec::new())
+  }
+
+  pub fn generations(&self) -> u32 {
+    [&self.seed_parent, &self.pollen_parent]
+      .into_iter()
+      .flatten()
+      .map(|parent| parent.generations() + 1)
+      .max()
+      .unwrap_or_default()
+  }
+}
+
+fn build<'a>(
+  details: &'a Details,
+  generation: u32,
+  max_generation: Option<u32>,
+  lookup: &impl Fn(&Parent) -> Option<&'a Details>,
+  path: &mut Vec<u32>,
+) -> Pedigree {
+  path.push(details.id);
+
+  let mut parent = |parent: &Option<Parent>| {
+    if max_generation.is_some_and(|max| generation >= max) {
+      return None;
+    }
+
+    let parent = lookup(parent.as_ref()?)?;
+
+    // some registrations name each other as parents
+    if path.contains(&parent.id) {
+      return None;
+    }
+
+    Some(Box::new(build(
+      parent,
+      generation + 1,
+      max_generation,
+      lookup,
+      path,
+    )))
+  };
+
+  let seed_parent = parent(&details.seed_parent);
+  let pollen_parent = parent(&details.pollen_parent);
+
+  path.pop();
+
+  Pedigree {
+    details: details.clone(),
+    seed_parent,
+    pollen_parent,
+  }
+}
+
+impl Dump {
+  /// Indexes the whole dump by name each time, so anything building many pedigrees should keep
+  /// its own index and call [`Pedigree::build`].
+  pub fn pedigree(&self, get: Get, max_generation: Option<u32>) -> Option<Pedigree> {
+    let root = self.get(get)?;
+
+    let names = self
+      .iter()
+      .map(|details| ((details.genus.as_str(), details.epithet.as_str()), details))
+      .collect::<HashMap<_, _>>();
+
+    Some(Pedigree::build(root, max_generation, &|parent| {
+      names
+        .get(&(parent.genus.as_str(), parent.epithet.as_str()))
+        .copied()
+    }))
+  }
+}
+
+#[test]
+fn pedigree() {
+  let grex = |id: u32, name: &str, seed: Option<&str>, pollen: Option<&str>| {
+    let parent = |epithet: &str| Parent {
+      genus: "Phalaenopsis".into(),
+      epithet: epithet.into(),
+    };
+
+    Details {
+      id,
+      genus: "Phalaenopsis".into(),
+      epithet: name.into(),
+      synonym: false,
+      synonym_details: None,
+      registrant_name: None,
+      originator_name: None,
+      date_of_registration: None,
+      seed_parent: seed.map(parent),
+      pollen_parent: pollen.map(parent),
+    }
+  };
+
+  let mut dump = Dump::empty();
+  dump.insert(grex(1, "amabilis", None, None));
+  dump.insert(grex(2, "schilleriana", None, None));
+  dump.insert(grex(
+    3,
+    "Elisabethae",
+    Some("amabilis"),
+    Some("schilleriana"),
+  ));
+  dump.insert(grex(4, "Doris", Some("Elisabethae"), Some("Unregistered")));
+  dump.insert(grex(5, "Loop", Some("Loop"), None));
+
+  let pedigree = dump.pedigree(Get { id: 4 }, None).unwrap();
+  assert_eq!(pedigree.generations(), 2);
+  assert!(pedigree.pollen_parent.is_none());
+
+  let seed = pedigree.seed_parent.as_ref().unwrap();
+  assert_eq!(seed.details.id, 3);
+  assert_eq!(seed.seed_parent.as_ref().unwrap().details.id, 1);
+  assert_eq!(seed.pollen_parent.as_ref().unwrap().details.id, 2);
+
+  let pedigree = dump.pedigree(Get { id: 4 }, Some(1)).unwrap();
+  assert_eq!(pedigree.generations(), 1);
+
+  assert_eq!(dump.pedigree(Get { id: 5 }, None).unwrap().generations(), 0);
+  assert!(dump.pedigree(Get { id: 6 }, None).is_none());
+}
@@ -0,0 +1,232 @@
+use std::{
+  collections::{BTreeMap, HashMap, HashSet, VecDeque},
+  ops::Deref,
+};
+
+use crate::{api::Details, csv::Dump};
+
+/// The registrations naming each grex as a parent. `D` is anything holding the dump, so the
+/// index can be kept alongside an `Arc<Dump>` as well as borrowed from one.
+pub struct ProgenyIndex<D> {
+  dump: D,
+  /// Ids of children, by parent genus and then epithet.
+  children: HashMap<String, HashMap<String, Vec<u32>>>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Descendant<'a> {
+  pub generation: u32,
+  pub details: &'a Details,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Progeny<'a> {
+  pub descendants: Vec<Descendant<'a>>,
+}
+
+impl<D: Deref<Target = Dump>> ProgenyIndex<D> {
+  pub fn new(dump: D) -> Self {
+    let mut children: HashMap<String, HashMap<String, Vec<u32>>> = HashMap::new();
+
+    for details in dump.iter() {
+      let parents = [&details.seed_parent, &details.pollen_parent];
+      let mut seen = HashSet::new();
+
+      for parent in parents.into_iter().flatten() {
+        // selfings list the same grex as both parents
+        if seen.insert((&parent.genus, &parent.epithet)) {
+          children
+            .entry(parent.genus.clone())
+            .or_default()
+            .entry(parent.epithet.clone())
+            .or_default()
+            .push(details.id);
+        }
+      }
+    }
+
+    for ids in children.values_mut().flat_map(HashMap::values_mut) {
+      ids.sort();
+    }
+
+    Self { dump, children }
+  }
+
+  pub fn dump(&self) -> &Dump {
+    &self.dump
+  }
+
+  fn ids(&self, genus: &str, epithet: &str) -> Option<&Vec<u32>> {
+    self.children.get(genus)?.get(epithet)
+  }
+
+  pub fn children(&self, genus: &str, epithet: &str) -> Vec<&Details> {
+    self
+      .ids(genus, epithet)
+      .into_iter()
+      .flatten()
+      .filter_map(|id| self.dump.data().get(id))
+      .collect()
+  }
+
+  pub fn progeny(&self, genus: &str, epithet: &str, max_generation: Option<u32>) -> Progeny<'_> {
+    let mut descendants = Vec::new();
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+
+    queue.push_back((genus, epithet, 0));
+
+    while let Some((genus, epithet, generation)) = queue.pop_front() {
+      if max_generation.is_some_and(|max| generation >= max) {
+        continue;
+      }
+
+      let Some(ids) = self.ids(genus, epithet) else {
+        continue;
+      };
+
+      for id in ids {
+        // some registrations name each other as parents
+        if !visited.insert(*id) {
+          continue;
+        }
+
+        let Some(details) = self.dump.data().get(id) else {
+          continue;
+        };
+
+        descendants.push(Descendant {
+          generation: generation + 1,
+          details,
+        });
+
+        queue.push_back((&details.genus, &details.epithet, generation + 1));
+      }
+    }
+
+    Progeny { descendants }
+  }
+}
+
+impl Progeny<'_> {
+  pub fn len(&self) -> usize {
+    self.descendants.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.descendants.is_empty()
+  }
+
+  pub fn generation(&self, generation: u32) -> impl Iterator<Item = &Details> {
+    self
+      .descendants
+      .iter()
+      .filter(move |d| d.generation == generation)
+      .map(|d| d.details)
+  }
+
+  pub fn max_generation(&self) -> u32 {
+    self
+      .descendants
+      .iter()
+      .map(|d| d.generation)
+      .max()
+      .unwrap_or_default()
+  }
+
+  pub fn counts_by_generation(&self) -> BTreeMap<u32, usize> {
+    let mut counts = BTreeMap::new();
+
+    for descendant in &self.descendants {
+      *counts.entry(descendant.generation).or_default() += 1;
+    }
+
+    counts
+  }
+
+  pub fn counts_by_year(&self) -> BTreeMap<Option<i32>, usize> {
+    let mut counts = BTreeMap::new();
+
+    for descendant in &self.descendants {
+      let year = descendant.details.date_of_registration.map(|d| d.year());
+      *counts.entry(year).or_default() += 1;
+    }
+
+    counts
+  }
+}
+
+impl Dump {
+  pub fn progeny_index(&self) -> ProgenyIndex<&Self> {
+    ProgenyIndex::new(self)
+  }
+}
+
+#[cfg(test)]
+fn grex(id: u32, name: &str, year: i32, seed: Option<&str>, pollen: Option<&str>) -> Details {
+  use time::{Date, Month};
+
+  use crate::api::get::Parent;
+
+  let parent = |epithet: &str| Parent {
+    genus: "Phalaenopsis".into(),
+    epithet: epithet.into(),
+  };
+
+  Details {
+    id,
+    genus: "Phalaenopsis".into(),
+    epithet: name.into(),
+    synonym: false,
+    synonym_details: None,
+    registrant_name: None,
+    originator_name: None,
+    date_of_registration: Some(Date::from_calendar_date(year, Month::January, 1).unwrap()),
+    seed_parent: seed.map(parent),
+    pollen_parent: pollen.map(parent),
+  }
+}
+
+#[test]
+fn progeny() {
+  let mut dump = Dump::empty();
+
+  dump.insert(grex(1, "amabilis", 1900, None, None));
+  dump.insert(grex(2, "schilleriana", 1900, None, None));
+  dump.insert(grex(
+    3,
+    "Elisabethae",
+    1927,
+    Some("amabilis"),
+    Some("schilleriana"),
+  ));
+  dump.insert(grex(
+    4,
+    "Doris",
+    1940,
+    Some("Elisabethae"),
+    Some("Elisabethae"),
+  ));
+  dump.insert(grex(5, "Grace Palm", 1940, Some("Doris"), Some("amabilis")));
+  dump.insert(grex(6, "Unrelated", 1950, Some("schilleriana"), None));
+
+  let index = dump.progeny_index();
+
+  let progeny = index.progeny("Phalaenopsis", "amabilis", None);
+  let mut ids = progeny
+    .descendants
+    .iter()
+    .map(|d| (d.details.id, d.generation))
+    .collect::<Vec<_>>();
+  ids.sort();
+
+  assert_eq!(ids, vec![(3, 1), (4, 2), (5, 1)]);
+  assert_eq!(progeny.max_generation(), 2);
+  assert_eq!(
+    progeny.counts_by_year(),
+    BTreeMap::from([(Some(1927), 1), (Some(1940), 2)])
+  );
+
+  let progeny = index.progeny("Phalaenopsis", "amabilis", Some(1));
+  assert_eq!(progeny.counts_by_generation(), BTreeMap::from([(1, 2)]));
+}
@@ -0,0 +1,236 @@
+use crate::{
+  abbreviation::Abbreviations,
+  api::{
+    get::Parent,
+    parentage::ParentageSearch,
+    search::{self, OnOff},
+    Details,
+    Search,
+    SearchResult,
+  },
+  csv::Dump,
+};
+
+pub(crate) enum Pattern<'a> {
+  Any,
+  Exact(&'a str),
+  Prefix(&'a str),
+  Wildcard(Vec<&'a str>),
+}
+
+impl<'a> Pattern<'a> {
+  pub(crate) fn new(pattern: Option<&'a str>, exact: bool) -> Self {
+    let Some(pattern) = pattern.map(str::trim).filter(|p| !p.is_empty()) else {
+      return Pattern::Any;
+    };
+
+    if pattern.contains(['*', '%']) {
+      Pattern::Wildcard(pattern.split(['*', '%']).collect())
+    } else if exact {
+      Pattern::Exact(pattern)
+    } else {
+      Pattern::Prefix(pattern)
+    }
+  }
+
+  /// Matches a genus exactly, expanding it first if it's an abbreviation.
+  pub(crate) fn genus(pattern: Option<&'a str>) -> Self {
+    Self::new(
+      pattern.map(|pattern| Abbreviations::bundled().expand(pattern)),
+      true,
+    )
+  }
+
+  fn matches(&self, value: &str) -> bool {
+    let value = value.to_lowercase();
+
+    match self {
+      Pattern::Any => true,
+      Pattern::Exact(pattern) => value == pattern.to_lowercase(),
+      Pattern::Prefix(pattern) => value.starts_with(&pattern.to_lowercase()),
+      Pattern::Wildcard(parts) => {
+        let (first, rest) = parts.split_first().unwrap();
+        let Some(mut value) = value.strip_prefix(&first.to_lowercase()) else {
+          return false;
+        };
+
+        for (i, part) in rest.iter().enumerate() {
+          let part = part.to_lowercase();
+
+          if i == rest.len() - 1 {
+            return value.ends_with(&part);
+          }
+
+          match value.find(&part) {
+            Some(index) => value = &value[index + part.len()..],
+            None => return false,
+          }
+        }
+
+        value.is_empty()
+      },
+    }
+  }
+
+  /// The same pattern for an `ILIKE`, or `None` if it matches anything.
+  #[cfg(feature = "postgres")]
+  pub(crate) fn like(&self) -> Option<String> {
+    let escape = |value: &str| value.replace('\\', "\\\\").replace('_', "\\_");
+
+    match self {
+      Pattern::Any => None,
+      Pattern::Exact(pattern) => Some(escape(pattern)),
+      Pattern::Prefix(pattern) => Some(format!("{}%", escape(pattern))),
+      Pattern::Wildcard(parts) => Some(
+        parts
+          .iter()
+          .map(|part| escape(part))
+          .collect::<Vec<_>>()
+          .join("%"),
+      ),
+    }
+  }
+}
+
+impl Dump {
+  pub fn search(&self, search: &Search) -> Vec<SearchResult> {
+    match search {
+      Search::Parentage(parentage_search) => self.parentage_search(parentage_search),
+      Search::Name(search) => self.name_search(search),
+    }
+  }
+
+  pub fn name_search(&self, search: &search::Search) -> Vec<SearchResult> {
+    let exact = matches!(search.exact, Some(OnOff::On));
+    let genus = Pattern::genus(search.genus.as_deref());
+    let grex = Pattern::new(search.grex.as_deref(), exact);
+
+    results(
+      self
+        .iter()
+        .filter(|details| genus.matches(&details.genus) && grex.matches(&details.epithet)),
+    )
+  }
+
+  pub fn parentage_search(&self, search: &ParentageSearch) -> Vec<SearchResult> {
+    let seed_genus = Pattern::genus(search.seed_genus.as_deref());
+    let seed_grex = Pattern::new(search.seed_grex.as_deref(), true);
+    let pollen_genus = Pattern::genus(search.pollen_genus.as_deref());
+    let pollen_grex = Pattern::new(search.pollen_grex.as_deref(), true);
+
+    let parent_matches = |parent: &Option<_>, genus: &Pattern, grex: &Pattern| match parent {
+      Some(Parent {
+        genus: parent_genus,
+        epithet,
+      }) => genus.matches(parent_genus) && grex.matches(epithet),
+      None => matches!((genus, grex), (Pattern::Any, Pattern::Any)),
+    };
+
+    results(self.iter().filter(|details| {
+      parent_matches(&details.seed_parent, &seed_genus, &seed_grex)
+        && parent_matches(&details.pollen_parent, &pollen_genus, &pollen_grex)
+    }))
+  }
+}
+
+fn results<'a>(details: impl Iterator<Item = &'a Details>) -> Vec<SearchResult> {
+  let mut res = details
+    .map(|details| SearchResult {
+      genus: details.genus.clone(),
+      grex: details.epithet.clone(),
+      id: details.id,
+    })
+    .collect::<Vec<_>>();
+
+  res.sort_by(|a, b| (&a.genus, &a.grex, a.id).cmp(&(&b.genus, &b.grex, b.id)));
+
+  res
+}
+
+#[test]
+fn offline_search() {
+  let details = |id: u32, genus: &str, epithet: &str, seed: Option<(&str, &str)>| Details {
+    id,
+    genus: genus.into(),
+    epithet: epithet.into(),
+    synonym: false,
+    synonym_details: None,
+    registrant_name: None,
+    originator_name: None,
+    date_of_registration: None,
+    seed_parent: seed.map(|(genus, epithet)| Parent {
+      genus: genus.into(),
+      epithet: epithet.into(),
+    }),
+    pollen_parent: None,
+  };
+
+  let mut dump = Dump::empty();
+  dump.insert(details(1, "Phalaenopsis", "schilleriana", None));
+  dump.insert(details(2, "Phalaenopsis", "Schiller's Gold", None));
+  dump.insert(details(
+    3,
+    "Phalaenopsis",
+    "Elisabethae",
+    Some(("Phalaenopsis", "schilleriana")),
+  ));
+  dump.insert(details(4, "Cattleya", "schilleriana", None));
+
+  let ids = |res: Vec<SearchResult>| res.into_iter().map(|r| r.id).collect::<Vec<_>>();
+
+  let search = search::Search {
+    genus: Some("Phalaenopsis".into()),
+    grex: Some("schiller".into()),
+    ..Default::default()
+  };
+  assert_eq!(ids(dump.name_search(&search)), vec![2, 1]);
+
+  let search = search::Search {
+    grex: Some("SCHILLERIANA".into()),
+    exact: Some(OnOff::On),
+    ..Default::default()
+  };
+  assert_eq!(ids(dump.name_search(&search)), vec![4, 1]);
+
+  let search = search::Search {
+    grex: Some("*abeth*".into()),
+    ..Default::default()
+  };
+  assert_eq!(ids(dump.name_search(&search)), vec![3]);
+
+  let search = search::Search {
+    genus: Some("phal.".into()),
+    grex: Some("schiller".into()),
+    ..Default::default()
+  };
+  assert_eq!(ids(dump.name_search(&search)), vec![2, 1]);
+
+  let search = search::Search {
+    genus: Some("phal".into()),
+    grex: Some("schiller".into()),
+    ..Default::default()
+  };
+  assert!(dump.name_search(&search).is_empty());
+
+  let search = Search::Parentage(ParentageSearch {
+    seed_genus: Some("Phal.".into()),
+    seed_grex: Some("schilleriana".into()),
+    ..Default::default()
+  });
+  assert_eq!(ids(dump.search(&search)), vec![3]);
+}
+
+#[cfg(feature = "postgres")]
+#[test]
+fn like() {
+  assert_eq!(Pattern::new(None, false).like(), None);
+  assert_eq!(Pattern::new(Some("Doris"), true).like().unwrap(), "Doris");
+  assert_eq!(
+    Pattern::new(Some("Do_ris"), false).like().unwrap(),
+    "Do\\_ris%"
+  );
+  assert_eq!(
+    Pattern::new(Some("*abeth*"), false).like().unwrap(),
+    "%abeth%"
+  );
+}
@@ -12,9 +12,45 @@ use crate::api::{
   Details,
 };
 
+/// How `date_of_registration` is written. Either is accepted when reading.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DateFormat {
+  /// ISO 8601, `2024-12-22`.
+  #[default]
+  Iso8601,
+  /// `2024/12/22`, which dumps used before ISO 8601.
+  Legacy,
+}
+
+impl DateFormat {
+  pub fn format(&self, date: Date) -> String {
+    let separator = match self {
+      DateFormat::Iso8601 => '-',
+      DateFormat::Legacy => '/',
+    };
+
+    format!(
+      "{:04}{separator}{:02}{separator}{:02}",
+      date.year(),
+      u8::from(date.month()),
+      date.day()
+    )
+  }
+
+  pub fn parse(value: &str) -> Result<Date, time::error::Parse> {
+    let value = value.trim();
+
+    Date::parse(value, format_description!("[year]-[month]-[day]"))
+      .or_else(|_| Date::parse(value, format_description!("[year]/[month]/[day]")))
+  }
+}
+
 pub(super) struct CsvDetails(pub Details);
 
-impl Serialize for CsvDetails {
+/// Writes a registration as a `dump.csv` row, with dates in the given format.
+pub(super) struct CsvDetailsRef<'a>(pub &'a Details, pub DateFormat);
+
+impl Serialize for CsvDetailsRef<'_> {
   fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
   where
     S: serde::Serializer,
@@ -30,7 +66,8 @@ impl Serialize for CsvDetails {
       date_of_registration,
       seed_parent,
       pollen_parent,
-    } = &self.0;
+    } = self.0;
+    let date_format = self.1;
 
     let mut s = serializer.serialize_struct("details", 13)?;
 
@@ -66,10 +103,7 @@ impl Serialize for CsvDetails {
     s.serialize_field("originator_name", &originator_name)?;
     s.serialize_field(
       "date_of_registration",
-      &date_of_registration.map(|date| {
-        let format = format_description!("[year]/[month]/[day]");
-        date.format(format).unwrap()
-      }),
+      &date_of_registration.map(|date| date_format.format(date)),
     )?;
 
     nested_genus_epithet!("seed_parent", seed_parent);
@@ -79,6 +113,40 @@ impl Serialize for CsvDetails {
   }
 }
 
+/// Every column of a `CsvDetails` row except `id`, formatted as it is written by default, with
+/// empty cells as `None`.
+pub(super) fn fields(details: &Details) -> Vec<(&'static str, Option<String>)> {
+  let synonym = details.synonym_details.as_ref();
+  let seed = details.seed_parent.as_ref();
+  let pollen = details.pollen_parent.as_ref();
+
+  [
+    ("genus", Some(details.genus.clone())),
+    ("epithet", Some(details.epithet.clone())),
+    ("synonym", Some(details.synonym.to_string())),
+    ("synonym_details.genus", synonym.map(|s| s.genus.clone())),
+    (
+      "synonym_details.epithet",
+      synonym.and_then(|s| s.epithet.clone()),
+    ),
+    ("registrant_name", details.registrant_name.clone()),
+    ("originator_name", details.originator_name.clone()),
+    (
+      "date_of_registration",
+      details
+        .date_of_registration
+        .map(|date| DateFormat::default().format(date)),
+    ),
+    ("seed_parent.genus", seed.map(|p| p.genus.clone())),
+    ("seed_parent.epithet", seed.map(|p| p.epithet.clone())),
+    ("pollen_parent.genus", pollen.map(|p| p.genus.clone())),
+    ("pollen_parent.epithet", pollen.map(|p| p.epithet.clone())),
+  ]
+  .into_iter()
+  .map(|(field, value)| (field, value.filter(|v| !v.trim().is_empty())))
+  .collect()
+}
+
 impl<'de> Deserialize<'de> for CsvDetails {
   fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
   where
@@ -133,10 +201,12 @@ impl<'de> Deserialize<'de> for CsvDetails {
             "originator_name" => originator_name = next(&mut map)?,
             "date_of_registration" => {
               if let Some(value) = next(&mut map)? {
-                let format: &[time::format_description::FormatItem<'_>] =
-                  format_description!("[year]/[month]/[day]");
-                let value = Date::parse(&value, format).map_err(serde::de::Error::custom)?;
-                date_of_registration = Some(value);
+                let date = DateFormat::parse(&value).map_err(|e| {
+                  serde::de::Error::custom(format_args!(
+                    "invalid date_of_registration {value:?}, expected YYYY-MM-DD or YYYY/MM/DD: {e}"
+                  ))
+                })?;
+                date_of_registration = Some(date);
               }
             },
             "seed_parent.genus" => seed_parent_genus = next(&mut map)?,
@@ -192,3 +262,50 @@ impl<'de> Deserialize<'de> for CsvDetails {
     deserializer.deserialize_struct("CsvDetails", FIELDS, CsvDetailsVisitor)
   }
 }
+
+#[test]
+fn dates() {
+  let csv = "id,genus,epithet,synonym,date_of_registration
+1,Phalaenopsis,Doris,false,1940-03-01
+2,Phalaenopsis,Golden Sands,false,1975/06/01
+3,Phalaenopsis,amabilis,false,
+";
+
+  let details = csv::Reader::from_reader(csv.as_bytes())
+    .deserialize()
+    .map(|record| record.map(|CsvDetails(details)| details))
+    .collect::<Result<Vec<_>, csv::Error>>()
+    .unwrap();
+
+  let dates = details
+    .iter()
+    .map(|details| details.date_of_registration.map(|date| date.to_string()))
+    .collect::<Vec<_>>();
+  assert_eq!(
+    dates,
+    [Some("1940-03-01".into()), Some("1975-06-01".into()), None]
+  );
+
+  let write = |date_format| {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+      .serialize(CsvDetailsRef(&details[0], date_format))
+      .unwrap();
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+  };
+  assert!(write(DateFormat::default()).contains(",1940-03-01,"));
+  assert!(write(DateFormat::Legacy).contains(",1940/03/01,"));
+
+  let err = csv::Reader::from_reader(
+    "id,genus,epithet,synonym,date_of_registration\n1,Phalaenopsis,Doris,false,01/03/1940\n"
+      .as_bytes(),
+  )
+  .deserialize::<CsvDetails>()
+  .next()
+  .unwrap()
+  .err()
+  .unwrap();
+  assert!(err
+    .to_string()
+    .contains("invalid date_of_registration \"01/03/1940\""));
+}
@@ -0,0 +1,277 @@
+//! Reading and writing `dump.csv` a row at a time, so large dumps never need to be held in memory
+//! twice.
+
+use std::{
+  ffi::OsString,
+  fs::File,
+  io::{BufReader, Read, Write},
+  path::{Path, PathBuf},
+};
+
+use tokio::io::AsyncWriteExt;
+
+use crate::{
+  api::Details,
+  csv::{
+    csv::Error,
+    serde::{CsvDetails, CsvDetailsRef, DateFormat},
+  },
+};
+
+/// Rows are written out once this many bytes have been buffered.
+const CHUNK: usize = 64 * 1024;
+
+/// Registrations read lazily from a dump.
+pub struct DumpReader<R: Read> {
+  records: csv::DeserializeRecordsIntoIter<R, CsvDetails>,
+}
+
+impl<R: Read> DumpReader<R> {
+  pub fn new(reader: R) -> Self {
+    Self {
+      records: csv::Reader::from_reader(reader).into_deserialize(),
+    }
+  }
+}
+
+impl DumpReader<BufReader<File>> {
+  pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+    Ok(Self::new(BufReader::new(File::open(path)?)))
+  }
+}
+
+impl<R: Read> Iterator for DumpReader<R> {
+  type Item = Result<Details, Error>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let record = self.records.next()?;
+
+    Some(
+      record
+        .map(|CsvDetails(details)| details)
+        .map_err(Error::from),
+    )
+  }
+}
+
+/// Serialised rows waiting to be written.
+pub(super) struct Rows {
+  writer: csv::Writer<Vec<u8>>,
+  date_format: DateFormat,
+}
+
+impl Rows {
+  pub fn new(date_format: DateFormat) -> Self {
+    Self {
+      writer: csv::Writer::from_writer(Vec::new()),
+      date_format,
+    }
+  }
+
+  pub fn push(&mut self, details: &Details) -> Result<(), Error> {
+    self
+      .writer
+      .serialize(CsvDetailsRef(details, self.date_format))?;
+
+    Ok(())
+  }
+
+  /// The rows pushed since the last call, once there are at least `min` bytes of them.
+  pub fn take(&mut self, min: usize) -> Result<Option<Vec<u8>>, Error> {
+    self.writer.flush()?;
+
+    if self.writer.get_ref().len() < min.max(1) {
+      return Ok(None);
+    }
+
+    // the header's already been written, if there is one
+    let writer = std::mem::replace(
+      &mut self.writer,
+      csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(Vec::new()),
+    );
+
+    Ok(Some(writer.into_inner().map_err(|e| e.into_error())?))
+  }
+
+  pub fn take_chunk(&mut self) -> Result<Option<Vec<u8>>, Error> {
+    self.take(CHUNK)
+  }
+}
+
+/// Writes a dump row by row to a temporary file next to `path`, which replaces `path` when
+/// [`DumpWriter::finish`] is called. If the writer is dropped first, or the process dies
+/// part-way through, whatever was at `path` is left untouched.
+pub struct DumpWriter {
+  rows: Rows,
+  // closed before `tmp` removes it
+  file: File,
+  tmp: Replacement,
+}
+
+impl DumpWriter {
+  pub fn create(path: impl AsRef<Path>) -> Result<Self, Error> {
+    Self::create_with_date_format(path, DateFormat::default())
+  }
+
+  pub fn create_with_date_format(
+    path: impl AsRef<Path>,
+    date_format: DateFormat,
+  ) -> Result<Self, Error> {
+    let tmp = Replacement::new(path.as_ref());
+
+    Ok(Self {
+      rows: Rows::new(date_format),
+      file: File::create(tmp.tmp())?,
+      tmp,
+    })
+  }
+
+  pub fn write(&mut self, details: &Details) -> Result<(), Error> {
+    self.rows.push(details)?;
+
+    if let Some(chunk) = self.rows.take_chunk()? {
+      self.file.write_all(&chunk)?;
+    }
+
+    Ok(())
+  }
+
+  pub fn finish(self) -> Result<(), Error> {
+    let Self {
+      mut rows,
+      mut file,
+      tmp,
+    } = self;
+
+    if let Some(chunk) = rows.take(0)? {
+      file.write_all(&chunk)?;
+    }
+
+    file.sync_all()?;
+    drop(file);
+
+    tmp.persist()?;
+
+    Ok(())
+  }
+}
+
+/// A temporary file next to `path`, which is renamed over `path` by [`Replacement::persist`]
+/// and removed if it's dropped first.
+pub(super) struct Replacement {
+  tmp: PathBuf,
+  path: PathBuf,
+  persisted: bool,
+}
+
+impl Replacement {
+  pub fn new(path: &Path) -> Self {
+    Self {
+      tmp: tmp_path(path),
+      path: path.to_path_buf(),
+      persisted: false,
+    }
+  }
+
+  /// Where to write the new contents.
+  pub fn tmp(&self) -> &Path {
+    &self.tmp
+  }
+
+  pub fn persist(mut self) -> std::io::Result<()> {
+    std::fs::rename(&self.tmp, &self.path)?;
+    self.persisted = true;
+
+    Ok(())
+  }
+
+  pub async fn persist_async(mut self) -> std::io::Result<()> {
+    tokio::fs::rename(&self.tmp, &self.path).await?;
+    self.persisted = true;
+
+    Ok(())
+  }
+}
+
+impl Drop for Replacement {
+  fn drop(&mut self) {
+    if !self.persisted {
+      let _ = std::fs::remove_file(&self.tmp);
+    }
+  }
+}
+
+/// Where a file is written before being renamed over `path`.
+pub(super) fn tmp_path(path: &Path) -> PathBuf {
+  let mut tmp = OsString::from(path.as_os_str());
+  tmp.push(".tmp");
+
+  tmp.into()
+}
+
+pub(super) fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+  let tmp = Replacement::new(path);
+
+  let mut file = File::create(tmp.tmp())?;
+  file.write_all(contents)?;
+  file.sync_all()?;
+  drop(file);
+
+  tmp.persist()
+}
+
+pub(super) async fn write_atomic_async(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+  let tmp = Replacement::new(path);
+
+  let mut file = tokio::fs::File::create(tmp.tmp()).await?;
+  file.write_all(contents).await?;
+  file.sync_all().await?;
+  drop(file);
+
+  tmp.persist_async().await
+}
+
+#[test]
+fn streaming() -> Result<(), Error> {
+  let dir = tempfile::tempdir()?;
+  let dir = dir.path();
+  let path = dir.join("dump.csv");
+  std::fs::write(&path, "untouched")?;
+
+  let details = (1..=3000)
+    .map(|id| Details {
+      id,
+      genus: "Phalaenopsis".into(),
+      epithet: format!("Grex {id}"),
+      synonym: false,
+      synonym_details: None,
+      registrant_name: None,
+      originator_name: None,
+      date_of_registration: None,
+      seed_parent: None,
+      pollen_parent: None,
+    })
+    .collect::<Vec<_>>();
+
+  let mut writer = DumpWriter::create(&path)?;
+  writer.write(&details[0])?;
+  drop(writer);
+
+  assert_eq!(std::fs::read_to_string(&path)?, "untouched");
+  assert!(!tmp_path(&path).exists());
+
+  let mut writer = DumpWriter::create(&path)?;
+  for details in &details {
+    writer.write(details)?;
+  }
+  writer.finish()?;
+
+  let read = DumpReader::from_path(&path)?.collect::<Result<Vec<_>, _>>()?;
+  assert_eq!(read.len(), 3000);
+  assert_eq!(read[2999].epithet, "Grex 3000");
+  assert!(!tmp_path(&path).exists());
+
+  Ok(())
+}
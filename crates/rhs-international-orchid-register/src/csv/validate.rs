@@ -0,0 +1,435 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use time::{Date, OffsetDateTime};
+
+use crate::{
+  api::Details,
+  csv::{serde::fields, Dump, PatchChange, Patches},
+  nothogenus::Nothogenera,
+};
+
+/// Codes are written to patch files to suppress issues, so existing names must not change.
+#[derive(
+  Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueCode {
+  UnresolvedParent,
+  UnresolvedSynonym,
+  DuplicateName,
+  FutureDate,
+  DateBeforeParent,
+  EmptyEpithet,
+  ReplacementCharacter,
+  NothogenusMismatch,
+}
+
+impl IssueCode {
+  pub const ALL: [IssueCode; 8] = [
+    IssueCode::UnresolvedParent,
+    IssueCode::UnresolvedSynonym,
+    IssueCode::DuplicateName,
+    IssueCode::FutureDate,
+    IssueCode::DateBeforeParent,
+    IssueCode::EmptyEpithet,
+    IssueCode::ReplacementCharacter,
+    IssueCode::NothogenusMismatch,
+  ];
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      IssueCode::UnresolvedParent => "unresolved_parent",
+      IssueCode::UnresolvedSynonym => "unresolved_synonym",
+      IssueCode::DuplicateName => "duplicate_name",
+      IssueCode::FutureDate => "future_date",
+      IssueCode::DateBeforeParent => "date_before_parent",
+      IssueCode::EmptyEpithet => "empty_epithet",
+      IssueCode::ReplacementCharacter => "replacement_character",
+      IssueCode::NothogenusMismatch => "nothogenus_mismatch",
+    }
+  }
+}
+
+impl Display for IssueCode {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown issue code {0:?}")]
+pub struct ParseIssueCodeError(String);
+
+impl FromStr for IssueCode {
+  type Err = ParseIssueCodeError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    IssueCode::ALL
+      .into_iter()
+      .find(|code| code.as_str() == s.trim())
+      .ok_or_else(|| ParseIssueCodeError(s.to_string()))
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct Issue {
+  pub id: u32,
+  pub code: IssueCode,
+  /// The dump column the issue is in, if it's about a single one.
+  pub field: Option<&'static str>,
+  pub message: String,
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct Validation {
+  pub issues: Vec<Issue>,
+  pub suppressed: Vec<Issue>,
+}
+
+impl Dump {
+  pub fn validate(&self) -> Validation {
+    self.validate_at(OffsetDateTime::now_utc().date())
+  }
+
+  fn validate_at(&self, today: Date) -> Validation {
+    let key =
+      |genus: &str, epithet: &str| (genus.trim().to_lowercase(), epithet.trim().to_lowercase());
+
+    let mut names = HashMap::<_, Vec<&Details>>::new();
+    for details in self.iter() {
+      names
+        .entry(key(&details.genus, &details.epithet))
+        .or_default()
+        .push(details);
+    }
+
+    let nothogenera = Nothogenera::bundled();
+
+    let mut issues = Vec::new();
+    let mut issue = |details: &Details, code, field, message: String| {
+      issues.push(Issue {
+        id: details.id,
+        code,
+        field,
+        message,
+      })
+    };
+
+    for details in self.iter() {
+      for (field, parent) in [
+        ("seed_parent", &details.seed_parent),
+        ("pollen_parent", &details.pollen_parent),
+      ] {
+        let Some(parent) = parent else {
+          continue;
+        };
+
+        let Some(matches) = names.get(&key(&parent.genus, &parent.epithet)) else {
+          issue(
+            details,
+            IssueCode::UnresolvedParent,
+            Some(field),
+            format!("{} {} is not in the dump", parent.genus, parent.epithet),
+          );
+          continue;
+        };
+
+        let parent_date = matches.iter().filter_map(|m| m.date_of_registration).min();
+
+        if let (Some(date), Some(parent_date)) = (details.date_of_registration, parent_date) {
+          if date < parent_date {
+            issue(
+              details,
+              IssueCode::DateBeforeParent,
+              Some("date_of_registration"),
+              format!(
+                "registered {date} but {field} {} {} was registered {parent_date}",
+                parent.genus, parent.epithet
+              ),
+            );
+          }
+        }
+      }
+
+      match &details.synonym_details {
+        Some(synonym) => {
+          let resolves = synonym
+            .epithet
+            .as_ref()
+            .is_some_and(|epithet| names.contains_key(&key(&synonym.genus, epithet)));
+
+          if !resolves {
+            issue(
+              details,
+              IssueCode::UnresolvedSynonym,
+              Some("synonym_details"),
+              format!(
+                "{} {} is not in the dump",
+                synonym.genus,
+                synonym.epithet.as_deref().unwrap_or_default()
+              ),
+            );
+          }
+        },
+        None if details.synonym => {
+          issue(
+            details,
+            IssueCode::UnresolvedSynonym,
+            Some("synonym_details"),
+            "marked as a synonym without saying of what".to_string(),
+          );
+        },
+        None => (),
+      }
+
+      if let Some(duplicates) = names
+        .get(&key(&details.genus, &details.epithet))
+        .filter(|duplicates| duplicates.len() > 1)
+      {
+        let mut others = duplicates
+          .iter()
+          .map(|d| d.id)
+          .filter(|id| *id != details.id)
+          .collect::<Vec<_>>();
+        others.sort();
+
+        issue(
+          details,
+          IssueCode::DuplicateName,
+          None,
+          format!("same name as {others:?}"),
+        );
+      }
+
+      if let Some(date) = details.date_of_registration.filter(|date| *date > today) {
+        issue(
+          details,
+          IssueCode::FutureDate,
+          Some("date_of_registration"),
+          format!("registered {date}, which is in the future"),
+        );
+      }
+
+      if details.epithet.trim().is_empty() {
+        issue(
+          details,
+          IssueCode::EmptyEpithet,
+          Some("epithet"),
+          "epithet is empty".to_string(),
+        );
+      }
+
+      if let Some(mismatch) = nothogenera.check(details) {
+        issue(
+          details,
+          IssueCode::NothogenusMismatch,
+          Some("genus"),
+          format!(
+            "parents make a {} rather than a {}",
+            mismatch.expected, mismatch.genus
+          ),
+        );
+      }
+
+      for (field, value) in fields(details) {
+        if value.is_some_and(|value| value.contains('\u{FFFD}')) {
+          issue(
+            details,
+            IssueCode::ReplacementCharacter,
+            Some(field),
+            format!("{field} contains U+FFFD"),
+          );
+        }
+      }
+    }
+
+    issues.sort_by(|a, b| (a.id, a.code, a.field).cmp(&(b.id, b.code, b.field)));
+
+    Validation {
+      issues,
+      suppressed: Vec::new(),
+    }
+  }
+}
+
+impl Validation {
+  /// Moves issues matched by `suppress` patches into `suppressed`. A suppression with a field
+  /// only matches issues in that field, or one of its nested columns.
+  pub fn suppress(&mut self, patches: &Patches) {
+    let suppressions = patches
+      .iter()
+      .filter_map(|patch| match &patch.change {
+        PatchChange::Suppress { code, field } => Some((patch.id, *code, field.as_deref())),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+
+    let suppressed = |issue: &Issue| {
+      suppressions.iter().any(|(id, code, field)| {
+        *id == issue.id
+          && *code == issue.code
+          && field.is_none_or(|field| {
+            issue
+              .field
+              .is_some_and(|f| f == field || f.starts_with(&format!("{field}.")))
+          })
+      })
+    };
+
+    let (suppressed, issues) = std::mem::take(&mut self.issues)
+      .into_iter()
+      .partition::<Vec<_>, _>(suppressed);
+
+    self.issues = issues;
+    self.suppressed.extend(suppressed);
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.issues.is_empty()
+  }
+
+  pub fn counts(&self) -> HashMap<IssueCode, usize> {
+    let mut counts = HashMap::new();
+
+    for issue in &self.issues {
+      *counts.entry(issue.code).or_default() += 1;
+    }
+
+    counts
+  }
+
+  pub fn write_csv(&self, writer: impl std::io::Write) -> Result<(), csv::Error> {
+    let mut writer = csv::Writer::from_writer(writer);
+
+    for issue in &self.issues {
+      writer.serialize(issue)?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+  }
+}
+
+#[test]
+fn validate() -> Result<(), crate::csv::PatchError> {
+  use time::Month;
+
+  use crate::api::get::{Parent, Synonym};
+
+  let date = |year| Date::from_calendar_date(year, Month::January, 1).unwrap();
+  let parent = |epithet: &str| {
+    Some(Parent {
+      genus: "Phalaenopsis".into(),
+      epithet: epithet.into(),
+    })
+  };
+  let details = |id: u32, epithet: &str, year: i32| Details {
+    id,
+    genus: "Phalaenopsis".into(),
+    epithet: epithet.into(),
+    synonym: false,
+    synonym_details: None,
+    registrant_name: None,
+    originator_name: None,
+    date_of_registration: Some(date(year)),
+    seed_parent: None,
+    pollen_parent: None,
+  };
+
+  let mut dump = Dump::empty();
+  dump.insert(details(1, "amabilis", 1900));
+  dump.insert(details(2, "Doris", 1940));
+  dump.insert(Details {
+    seed_parent: parent("Doris"),
+    pollen_parent: parent("Missing"),
+    ..details(3, "Golden Sands", 1930)
+  });
+  dump.insert(Details {
+    synonym: true,
+    synonym_details: Some(Synonym {
+      genus: "Phalaenopsis".into(),
+      epithet: Some("DORIS".into()),
+    }),
+    ..details(4, "doris", 1950)
+  });
+  dump.insert(Details {
+    synonym: true,
+    registrant_name: Some("Br\u{FFFD}thers".into()),
+    ..details(5, " ", 2030)
+  });
+  dump.insert(Details {
+    genus: "Brassavola".into(),
+    ..details(6, "nodosa", 1900)
+  });
+  dump.insert(Details {
+    genus: "Cattleya".into(),
+    ..details(7, "labiata", 1900)
+  });
+  dump.insert(Details {
+    seed_parent: Some(Parent {
+      genus: "Brassavola".into(),
+      epithet: "nodosa".into(),
+    }),
+    pollen_parent: Some(Parent {
+      genus: "Cattleya".into(),
+      epithet: "labiata".into(),
+    }),
+    ..details(8, "Wrong Genus", 1960)
+  });
+  dump.insert(Details {
+    seed_parent: parent("am\u{FFFD}bilis"),
+    ..details(9, "Mojibake", 1960)
+  });
+
+  let mut validation = dump.validate_at(date(2025));
+  let issues = validation
+    .issues
+    .iter()
+    .map(|issue| (issue.id, issue.code, issue.field))
+    .collect::<Vec<_>>();
+
+  assert_eq!(
+    issues,
+    [
+      (2, IssueCode::DuplicateName, None),
+      (3, IssueCode::UnresolvedParent, Some("pollen_parent")),
+      (3, IssueCode::DateBeforeParent, Some("date_of_registration")),
+      (4, IssueCode::DuplicateName, None),
+      (5, IssueCode::UnresolvedSynonym, Some("synonym_details")),
+      (5, IssueCode::FutureDate, Some("date_of_registration")),
+      (5, IssueCode::EmptyEpithet, Some("epithet")),
+      (5, IssueCode::ReplacementCharacter, Some("registrant_name")),
+      (8, IssueCode::NothogenusMismatch, Some("genus")),
+      (9, IssueCode::UnresolvedParent, Some("seed_parent")),
+      (
+        9,
+        IssueCode::ReplacementCharacter,
+        Some("seed_parent.epithet")
+      ),
+    ]
+  );
+
+  let patches: Patches = "id,action,field,value,upstream,reason
+2,suppress,,duplicate_name,,Doris and doris are different grexes
+3,suppress,pollen_parent,unresolved_parent,,
+5,suppress,epithet,future_date,,wrong field
+9,suppress,seed_parent,replacement_character,,
+9,suppress,seed_parent.epithet,unresolved_parent,,too specific
+"
+  .parse()?;
+
+  validation.suppress(&patches);
+  assert_eq!(validation.suppressed.len(), 3);
+  assert_eq!(validation.issues.len(), 8);
+  assert!(validation
+    .issues
+    .iter()
+    .any(|issue| issue.id == 9 && issue.code == IssueCode::UnresolvedParent));
+  assert_eq!(validation.counts()[&IssueCode::DuplicateName], 1);
+
+  assert_eq!("future_date".parse(), Ok(IssueCode::FutureDate));
+  assert!("future".parse::<IssueCode>().is_err());
+
+  Ok(())
+}
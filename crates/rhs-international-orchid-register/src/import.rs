@@ -0,0 +1,69 @@
+use std::{collections::HashMap, future::Future};
+
+use crate::api::Details;
+
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct Import {
+  pub inserted: usize,
+  /// Entries that still couldn't be inserted once no more progress was being made, usually
+  /// because a parent or synonym they refer to is missing.
+  pub failed: Vec<u32>,
+}
+
+/// Calls `insert` on `details`, retrying entries it returns `false` for as long as each pass
+/// inserts something, since an entry can only go in after its parents and synonyms have. Any
+/// error ends the import.
+pub(crate) async fn ordered<Fut, E>(
+  details: impl IntoIterator<Item = Details>,
+  mut progress: impl FnMut(usize, usize),
+  mut insert: impl FnMut(Details) -> Fut,
+) -> Result<Import, E>
+where
+  Fut: Future<Output = Result<bool, E>>,
+{
+  let mut details = details
+    .into_iter()
+    .map(|details| (details.id, details))
+    .collect::<HashMap<_, _>>();
+  let len = details.len();
+
+  let mut prev_len = len;
+  let mut asc = true;
+  while !details.is_empty() {
+    let mut values = details.values().cloned().collect::<Vec<_>>();
+
+    values.sort_by_key(|v| v.date_of_registration);
+    if !asc {
+      values.reverse();
+    }
+
+    progress(len - details.len(), len);
+
+    for value in values {
+      let id = value.id;
+      if insert(value).await? {
+        details.remove(&id);
+      }
+    }
+
+    if prev_len == details.len() {
+      if asc {
+        asc = false;
+        continue;
+      }
+
+      break;
+    }
+
+    prev_len = details.len();
+    asc = true;
+  }
+
+  let mut failed = details.into_keys().collect::<Vec<_>>();
+  failed.sort();
+
+  Ok(Import {
+    inserted: len - failed.len(),
+    failed,
+  })
+}
@@ -1,3 +1,14 @@
+pub mod abbreviation;
 pub mod api;
+#[cfg(all(feature = "csv", feature = "scrape"))]
+pub mod crawler;
+#[cfg(feature = "csv")]
 pub mod csv;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+mod import;
+pub mod nothogenus;
+#[cfg(feature = "postgres")]
 pub mod sql;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod table;
@@ -0,0 +1,198 @@
+//! Nothogenera, the genera given to hybrids between genera, and the names they've had.
+//!
+//! A grex's genus follows from the natural genera in its parents: Brassavola × Cattleya is a
+//! Brassocattleya, and a Brassocattleya × Laelia a Brassolaeliocattleya. Genera that aren't in
+//! the tables are taken to be natural genera.
+
+use std::{
+  collections::{BTreeSet, HashMap},
+  sync::OnceLock,
+};
+
+use crate::{
+  api::Details,
+  table::{rows, ParseError},
+};
+
+const NOTHOGENERA: &str = include_str!("../data/nothogenera.csv");
+const RENAMED: &str = include_str!("../data/renamed_genera.csv");
+
+/// A registration whose genus isn't the one its parents' genera make.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Mismatch {
+  pub genus: String,
+  pub expected: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Nothogenera {
+  by_genera: HashMap<BTreeSet<String>, String>,
+  genera: HashMap<String, BTreeSet<String>>,
+  renamed: HashMap<String, String>,
+}
+
+impl Nothogenera {
+  /// The tables checked in to `data/nothogenera.csv` and `data/renamed_genera.csv`.
+  pub fn bundled() -> &'static Self {
+    static BUNDLED: OnceLock<Nothogenera> = OnceLock::new();
+
+    BUNDLED.get_or_init(|| Self::parse(NOTHOGENERA, RENAMED).expect("bundled nothogenera"))
+  }
+
+  /// Reads tables in the same format as the bundled ones: a header, then `nothogenus,genera`
+  /// with the genera separated by spaces, and `genus,current`. Lines starting with `#` are
+  /// skipped.
+  pub fn parse(nothogenera: &str, renamed: &str) -> Result<Self, ParseError> {
+    let mut s = Self::default();
+
+    for (line, nothogenus, genera) in rows(nothogenera)? {
+      let genera = genera
+        .split_whitespace()
+        .map(str::to_string)
+        .collect::<BTreeSet<_>>();
+
+      if genera.len() < 2 {
+        return Err(ParseError {
+          line,
+          message: format!("{nothogenus} needs at least two genera"),
+        });
+      }
+
+      s.by_genera.insert(genera.clone(), nothogenus.to_string());
+      s.genera.insert(nothogenus.to_string(), genera);
+    }
+
+    for (_, genus, current) in rows(renamed)? {
+      s.renamed.insert(genus.to_string(), current.to_string());
+    }
+
+    Ok(s)
+  }
+
+  /// The name `genus` is used under now, which is `genus` itself unless it's been renamed.
+  pub fn current<'a>(&'a self, genus: &'a str) -> &'a str {
+    let mut genus = genus.trim();
+
+    // guards against a table that renames in a loop
+    for _ in 0..self.renamed.len() {
+      match self.renamed.get(genus) {
+        Some(current) => genus = current,
+        None => break,
+      }
+    }
+
+    genus
+  }
+
+  /// The natural genera in `genus`.
+  pub fn genera<'a>(&'a self, genus: &'a str) -> BTreeSet<&'a str> {
+    let genus = self.current(genus);
+
+    match self.genera.get(genus) {
+      Some(genera) => genera.iter().map(String::as_str).collect(),
+      None => BTreeSet::from([genus]),
+    }
+  }
+
+  /// The genus for a grex combining `genera`, if it's known.
+  pub fn nothogenus<'a>(&'a self, genera: &BTreeSet<&'a str>) -> Option<&'a str> {
+    if genera.len() == 1 {
+      return genera.first().copied();
+    }
+
+    let genera = genera.iter().map(|genus| genus.to_string()).collect();
+
+    self.by_genera.get(&genera).map(String::as_str)
+  }
+
+  /// The genus for a grex between parents in `seed` and `pollen`, if it's known.
+  pub fn expected<'a>(&'a self, seed: &'a str, pollen: &'a str) -> Option<&'a str> {
+    let mut genera = self.genera(seed);
+    genera.extend(self.genera(pollen));
+
+    self.nothogenus(&genera)
+  }
+
+  /// Compares the genus `details` is registered in with the one its parents make, using the
+  /// current names of both.
+  pub fn check(&self, details: &Details) -> Option<Mismatch> {
+    let seed = details.seed_parent.as_ref()?;
+    let pollen = details.pollen_parent.as_ref()?;
+
+    let expected = self.expected(&seed.genus, &pollen.genus)?;
+
+    (self.current(&details.genus) != expected).then(|| Mismatch {
+      genus: details.genus.clone(),
+      expected: expected.to_string(),
+    })
+  }
+}
+
+#[test]
+fn bundled() {
+  use crate::api::Parent;
+
+  let table = Nothogenera::bundled();
+
+  assert_eq!(
+    table.expected("Brassavola", "Cattleya"),
+    Some("Brassocattleya")
+  );
+  assert_eq!(
+    table.expected("Brassocattleya", "Laelia"),
+    Some("Brassolaeliocattleya")
+  );
+  assert_eq!(table.expected("Cattleya", "Cattleya"), Some("Cattleya"));
+  assert_eq!(
+    table.expected("Doritis", "Phalaenopsis"),
+    Some("Phalaenopsis")
+  );
+  assert_eq!(
+    table.expected("Odontoglossum", "Cochlioda"),
+    Some("Oncidium")
+  );
+  assert_eq!(
+    table.expected("Vuylstekeara", "Oncidium"),
+    Some("Miltonidium")
+  );
+  assert_eq!(table.expected("Cattleya", "Dendrobium"), None);
+  assert_eq!(table.current("Wilsonara"), "Oncidium");
+  assert_eq!(table.current("Cattleya"), "Cattleya");
+
+  let grex = |genus: &str, seed: &str, pollen: &str| Details {
+    id: 1,
+    genus: genus.into(),
+    epithet: "Test".into(),
+    synonym: false,
+    synonym_details: None,
+    registrant_name: None,
+    originator_name: None,
+    date_of_registration: None,
+    seed_parent: Some(Parent {
+      genus: seed.into(),
+      epithet: "a".into(),
+    }),
+    pollen_parent: Some(Parent {
+      genus: pollen.into(),
+      epithet: "b".into(),
+    }),
+  };
+
+  assert_eq!(
+    table.check(&grex("Laeliocattleya", "Brassavola", "Cattleya")),
+    Some(Mismatch {
+      genus: "Laeliocattleya".into(),
+      expected: "Brassocattleya".into(),
+    })
+  );
+  // registered before Ascocentrum was sunk into Vanda
+  assert_eq!(
+    table.check(&grex("Ascocenda", "Ascocentrum", "Vanda")),
+    None
+  );
+  assert_eq!(table.check(&grex("Cattleya", "Cattleya", "Mystery")), None);
+
+  let err = Nothogenera::parse("nothogenus,genera\nCattleya,Cattleya\n", "").unwrap_err();
+  assert_eq!(err.line, 2);
+}
@@ -20,6 +20,18 @@ pub struct Details {
   pub pollen_parent: Option<Uuid>,
 }
 
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Descendant {
+  pub generation: i32,
+  pub details: Details,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct YearCount {
+  pub year: Option<i32>,
+  pub count: i64,
+}
+
 impl Details {
   pub async fn insert(
     details: &crate::api::Details,
@@ -136,6 +148,54 @@ impl Details {
         .await
     }
   }
+
+  pub async fn progeny(
+    &self,
+    max_generation: Option<i32>,
+    pool: impl PgExecutor<'_>,
+  ) -> Result<Vec<Descendant>, sqlx::Error> {
+    let rows = sqlx::query_file!("sql/details/progeny.sql", self.id, max_generation)
+      .fetch_all(pool)
+      .await?;
+
+    let descendants = rows
+      .into_iter()
+      .map(|row| Descendant {
+        generation: row.generation,
+        details: Details {
+          id: row.id,
+          registar_id: row.registar_id,
+          genus: row.genus,
+          epithet: row.epithet,
+          synonym: row.synonym,
+          synonym_genus: row.synonym_genus,
+          synonym_epithet: row.synonym_epithet,
+          registrant_name: row.registrant_name,
+          originator_name: row.originator_name,
+          date_of_registration: row.date_of_registration,
+          seed_parent: row.seed_parent,
+          pollen_parent: row.pollen_parent,
+        },
+      })
+      .collect();
+
+    Ok(descendants)
+  }
+
+  pub async fn progeny_counts_by_year(
+    &self,
+    max_generation: Option<i32>,
+    pool: impl PgExecutor<'_>,
+  ) -> Result<Vec<YearCount>, sqlx::Error> {
+    sqlx::query_file_as!(
+      YearCount,
+      "sql/details/progeny_counts.sql",
+      self.id,
+      max_generation
+    )
+    .fetch_all(pool)
+    .await
+  }
 }
 
 // impl From<crate::api::Details> for InsertDetails {
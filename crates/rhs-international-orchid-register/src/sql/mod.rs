@@ -1,5 +1,11 @@
+use sqlx::PgPool;
+
 pub mod details;
 pub mod genera;
+#[cfg(feature = "csv")]
+pub mod registrations;
+
+pub use crate::import::Import;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -7,4 +13,29 @@ pub enum Error {
   NotFound,
   #[error(transparent)]
   Sqlx(#[from] sqlx::Error),
+  #[error(transparent)]
+  Migrate(#[from] sqlx::migrate::MigrateError),
+}
+
+pub async fn migrate(pool: &PgPool) -> Result<(), Error> {
+  sqlx::migrate!("./migrations").run(pool).await?;
+
+  Ok(())
+}
+
+/// Inserts `details`, parents and synonyms first.
+pub async fn import(
+  details: impl IntoIterator<Item = crate::api::Details>,
+  pool: &PgPool,
+  progress: impl FnMut(usize, usize),
+) -> Result<Import, Error> {
+  crate::import::ordered(details, progress, |value| async move {
+    // a parent or synonym that hasn't been inserted yet
+    match details::Details::insert(&value, pool).await {
+      Ok(_) => Ok(true),
+      Err(Error::NotFound) => Ok(false),
+      Err(e) => Err(e),
+    }
+  })
+  .await
 }
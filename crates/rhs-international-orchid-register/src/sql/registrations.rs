@@ -0,0 +1,210 @@
+//! Queries that return registrations in the same shape as the register itself, rather than as
+//! rows with ids pointing at other rows.
+
+use std::collections::HashMap;
+
+use sqlx::PgExecutor;
+use time::Date;
+
+use crate::{
+  api::{
+    get::{Parent, Synonym},
+    parentage::ParentageSearch,
+    search::{self, OnOff},
+    Details,
+    Get,
+    SearchResult,
+  },
+  csv::{Pattern, Pedigree},
+};
+
+struct Registration {
+  registar_id: i32,
+  genus: String,
+  epithet: String,
+  synonym: bool,
+  synonym_genus: Option<String>,
+  synonym_epithet: Option<String>,
+  registrant_name: Option<String>,
+  originator_name: Option<String>,
+  date_of_registration: Option<Date>,
+  seed_genus: Option<String>,
+  seed_epithet: Option<String>,
+  pollen_genus: Option<String>,
+  pollen_epithet: Option<String>,
+}
+
+impl From<Registration> for Details {
+  fn from(value: Registration) -> Self {
+    let parent = |genus: Option<String>, epithet: Option<String>| {
+      Some(Parent {
+        genus: genus?,
+        epithet: epithet?,
+      })
+    };
+
+    Details {
+      id: value.registar_id as u32,
+      genus: value.genus,
+      epithet: value.epithet,
+      synonym: value.synonym,
+      synonym_details: value.synonym_genus.map(|genus| Synonym {
+        genus,
+        epithet: value.synonym_epithet,
+      }),
+      registrant_name: value.registrant_name,
+      originator_name: value.originator_name,
+      date_of_registration: value.date_of_registration,
+      seed_parent: parent(value.seed_genus, value.seed_epithet),
+      pollen_parent: parent(value.pollen_genus, value.pollen_epithet),
+    }
+  }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Descendant {
+  pub generation: u32,
+  pub details: Details,
+}
+
+pub async fn get(get: Get, pool: impl PgExecutor<'_>) -> Result<Option<Details>, sqlx::Error> {
+  let id = get.id as i32;
+
+  let res = sqlx::query_file_as!(Registration, "sql/registrations/get.sql", id)
+    .fetch_optional(pool)
+    .await?;
+
+  Ok(res.map(Details::from))
+}
+
+/// Matches like [`crate::csv::Dump::name_search`].
+pub async fn name_search(
+  search: &search::Search,
+  pool: impl PgExecutor<'_>,
+) -> Result<Vec<SearchResult>, sqlx::Error> {
+  let exact = matches!(search.exact, Some(OnOff::On));
+  let genus = Pattern::genus(search.genus.as_deref()).like();
+  let grex = Pattern::new(search.grex.as_deref(), exact).like();
+
+  let rows = sqlx::query_file!("sql/registrations/name_search.sql", genus, grex)
+    .fetch_all(pool)
+    .await?;
+
+  Ok(
+    rows
+      .into_iter()
+      .map(|row| SearchResult {
+        genus: row.genus,
+        grex: row.grex,
+        id: row.id as u32,
+      })
+      .collect(),
+  )
+}
+
+/// Matches like [`crate::csv::Dump::parentage_search`].
+pub async fn parentage_search(
+  search: &ParentageSearch,
+  pool: impl PgExecutor<'_>,
+) -> Result<Vec<SearchResult>, sqlx::Error> {
+  let genus = |pattern: &Option<String>| Pattern::genus(pattern.as_deref()).like();
+  let grex = |pattern: &Option<String>| Pattern::new(pattern.as_deref(), true).like();
+
+  let rows = sqlx::query_file!(
+    "sql/registrations/parentage_search.sql",
+    genus(&search.seed_genus),
+    grex(&search.seed_grex),
+    genus(&search.pollen_genus),
+    grex(&search.pollen_grex)
+  )
+  .fetch_all(pool)
+  .await?;
+
+  Ok(
+    rows
+      .into_iter()
+      .map(|row| SearchResult {
+        genus: row.genus,
+        grex: row.grex,
+        id: row.id as u32,
+      })
+      .collect(),
+  )
+}
+
+pub async fn pedigree(
+  get: Get,
+  max_generation: Option<u32>,
+  pool: impl PgExecutor<'_>,
+) -> Result<Option<Pedigree>, sqlx::Error> {
+  let id = get.id as i32;
+  let generations = max_generation.map(|max| max as i32);
+
+  let ancestors = sqlx::query_file_as!(
+    Registration,
+    "sql/registrations/pedigree.sql",
+    id,
+    generations
+  )
+  .fetch_all(pool)
+  .await?
+  .into_iter()
+  .map(Details::from)
+  .collect::<Vec<_>>();
+
+  let Some(root) = ancestors.iter().find(|details| details.id == get.id) else {
+    return Ok(None);
+  };
+
+  let names = ancestors
+    .iter()
+    .map(|details| ((details.genus.as_str(), details.epithet.as_str()), details))
+    .collect::<HashMap<_, _>>();
+
+  Ok(Some(Pedigree::build(root, max_generation, &|parent| {
+    names
+      .get(&(parent.genus.as_str(), parent.epithet.as_str()))
+      .copied()
+  })))
+}
+
+/// Every registration descended from `get`, ordered by generation and then id.
+pub async fn progeny(
+  get: Get,
+  max_generation: Option<u32>,
+  pool: impl PgExecutor<'_>,
+) -> Result<Vec<Descendant>, sqlx::Error> {
+  let id = get.id as i32;
+  let generations = max_generation.map(|max| max as i32);
+
+  let rows = sqlx::query_file!("sql/registrations/progeny.sql", id, generations)
+    .fetch_all(pool)
+    .await?;
+
+  let mut descendants = rows
+    .into_iter()
+    .map(|row| Descendant {
+      generation: row.generation as u32,
+      details: Registration {
+        registar_id: row.registar_id,
+        genus: row.genus,
+        epithet: row.epithet,
+        synonym: row.synonym,
+        synonym_genus: row.synonym_genus,
+        synonym_epithet: row.synonym_epithet,
+        registrant_name: row.registrant_name,
+        originator_name: row.originator_name,
+        date_of_registration: row.date_of_registration,
+        seed_genus: row.seed_genus,
+        seed_epithet: row.seed_epithet,
+        pollen_genus: row.pollen_genus,
+        pollen_epithet: row.pollen_epithet,
+      }
+      .into(),
+    })
+    .collect::<Vec<_>>();
+
+  descendants.sort_by_key(|d| (d.generation, d.details.id));
+
+  Ok(descendants)
+}
@@ -0,0 +1,202 @@
+use sqlx::SqliteExecutor;
+use time::Date;
+
+use crate::sqlite::{genera::Genus, Error};
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct Details {
+  pub id: i64,
+  pub registar_id: i32,
+  pub genus: i64,
+  pub epithet: String,
+  pub synonym: bool,
+  pub synonym_genus: Option<i64>,
+  pub synonym_epithet: Option<i64>,
+  pub registrant_name: Option<String>,
+  pub originator_name: Option<String>,
+  pub date_of_registration: Option<Date>,
+  pub seed_parent: Option<i64>,
+  pub pollen_parent: Option<i64>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct Descendant {
+  pub generation: i32,
+  #[sqlx(flatten)]
+  pub details: Details,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct YearCount {
+  pub year: Option<i32>,
+  pub count: i64,
+}
+
+impl Details {
+  pub async fn insert(
+    details: &crate::api::Details,
+    pool: impl SqliteExecutor<'_> + Copy,
+  ) -> Result<Self, Error> {
+    let res = Details::get_from_name(&details.genus, &details.epithet, pool).await?;
+    if let Some(res) = res {
+      return Ok(res);
+    }
+
+    let genus = Genus::insert(&details.genus, pool).await?;
+
+    let (synonym_genus, synonym_epithet) = match (details.synonym, &details.synonym_details) {
+      (true, Some(details)) => {
+        let genus = Genus::insert(&details.genus, pool).await?;
+
+        let epithet = match &details.epithet {
+          Some(epithet) => {
+            let synonym = Details::get_from_name(&genus.name, epithet, pool)
+              .await?
+              .ok_or(Error::NotFound)?;
+
+            Some(synonym.id)
+          },
+          None => None,
+        };
+
+        (Some(genus.id), epithet)
+      },
+      _ => (None, None),
+    };
+
+    let mut parents = [None, None];
+    for (id, parent) in parents
+      .iter_mut()
+      .zip([&details.seed_parent, &details.pollen_parent])
+    {
+      if let Some(parent) = parent {
+        let parent = Details::get_from_name(&parent.genus, &parent.epithet, pool)
+          .await?
+          .ok_or(Error::NotFound)?;
+
+        *id = Some(parent.id);
+      }
+    }
+    let [seed_parent, pollen_parent] = parents;
+
+    let res = sqlx::query_as(include_str!("../../sql/sqlite/details/insert.sql"))
+      .bind(details.id as i32)
+      .bind(genus.id)
+      .bind(&details.epithet)
+      .bind(details.synonym)
+      .bind(synonym_genus)
+      .bind(synonym_epithet)
+      .bind(&details.registrant_name)
+      .bind(&details.originator_name)
+      .bind(details.date_of_registration)
+      .bind(seed_parent)
+      .bind(pollen_parent)
+      .fetch_one(pool)
+      .await?;
+
+    Ok(res)
+  }
+
+  pub async fn get_from_id(
+    id: i64,
+    pool: impl SqliteExecutor<'_>,
+  ) -> Result<Option<Self>, sqlx::Error> {
+    sqlx::query_as("SELECT * FROM details WHERE id = ?1")
+      .bind(id)
+      .fetch_optional(pool)
+      .await
+  }
+
+  pub async fn get_from_registar_id(
+    registar_id: u32,
+    pool: impl SqliteExecutor<'_>,
+  ) -> Result<Option<Self>, sqlx::Error> {
+    sqlx::query_as("SELECT * FROM details WHERE registar_id = ?1")
+      .bind(registar_id as i32)
+      .fetch_optional(pool)
+      .await
+  }
+
+  pub async fn get_from_name(
+    genus: impl AsRef<str>,
+    epithet: impl AsRef<str>,
+    pool: impl SqliteExecutor<'_>,
+  ) -> Result<Option<Self>, sqlx::Error> {
+    let genus = genus.as_ref();
+    let epithet = epithet.as_ref();
+
+    if epithet.contains('�') {
+      let epithet = epithet.replace('�', "%");
+      sqlx::query_as(include_str!(
+        "../../sql/sqlite/details/get_from_name_unicode.sql"
+      ))
+      .bind(genus)
+      .bind(epithet)
+      .fetch_optional(pool)
+      .await
+    } else {
+      sqlx::query_as(include_str!("../../sql/sqlite/details/get_from_name.sql"))
+        .bind(genus)
+        .bind(epithet)
+        .fetch_optional(pool)
+        .await
+    }
+  }
+
+  /// The registrations this one is a synonym of, by genus and then epithet.
+  pub async fn synonym_of(
+    &self,
+    pool: impl SqliteExecutor<'_> + Copy,
+  ) -> Result<(Option<Genus>, Option<Self>), sqlx::Error> {
+    let genus = match self.synonym_genus {
+      Some(id) => Genus::get_from_id(id, pool).await?,
+      None => None,
+    };
+    let epithet = match self.synonym_epithet {
+      Some(id) => Details::get_from_id(id, pool).await?,
+      None => None,
+    };
+
+    Ok((genus, epithet))
+  }
+
+  pub async fn parents(
+    &self,
+    pool: impl SqliteExecutor<'_> + Copy,
+  ) -> Result<(Option<Self>, Option<Self>), sqlx::Error> {
+    let seed_parent = match self.seed_parent {
+      Some(id) => Details::get_from_id(id, pool).await?,
+      None => None,
+    };
+    let pollen_parent = match self.pollen_parent {
+      Some(id) => Details::get_from_id(id, pool).await?,
+      None => None,
+    };
+
+    Ok((seed_parent, pollen_parent))
+  }
+
+  pub async fn progeny(
+    &self,
+    max_generation: Option<i32>,
+    pool: impl SqliteExecutor<'_>,
+  ) -> Result<Vec<Descendant>, sqlx::Error> {
+    sqlx::query_as(include_str!("../../sql/sqlite/details/progeny.sql"))
+      .bind(self.id)
+      .bind(max_generation)
+      .fetch_all(pool)
+      .await
+  }
+
+  pub async fn progeny_counts_by_year(
+    &self,
+    max_generation: Option<i32>,
+    pool: impl SqliteExecutor<'_>,
+  ) -> Result<Vec<YearCount>, sqlx::Error> {
+    sqlx::query_as(include_str!("../../sql/sqlite/details/progeny_counts.sql"))
+      .bind(self.id)
+      .bind(max_generation)
+      .fetch_all(pool)
+      .await
+  }
+}
@@ -0,0 +1,49 @@
+use sqlx::SqliteExecutor;
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct Genus {
+  pub id: i64,
+  pub name: String,
+}
+
+impl Genus {
+  pub async fn insert(
+    name: impl AsRef<str>,
+    pool: impl SqliteExecutor<'_> + Copy,
+  ) -> Result<Self, sqlx::Error> {
+    let name = name.as_ref();
+
+    let existing = Genus::get_from_name(name, pool).await;
+
+    if let Ok(Some(genus)) = existing {
+      return Ok(genus);
+    }
+
+    sqlx::query_as(include_str!("../../sql/sqlite/genera/insert.sql"))
+      .bind(name)
+      .fetch_one(pool)
+      .await
+  }
+
+  pub async fn get_from_id(
+    id: i64,
+    pool: impl SqliteExecutor<'_>,
+  ) -> Result<Option<Self>, sqlx::Error> {
+    sqlx::query_as("SELECT * FROM genera WHERE id = ?1")
+      .bind(id)
+      .fetch_optional(pool)
+      .await
+  }
+
+  pub async fn get_from_name(
+    name: impl AsRef<str>,
+    pool: impl SqliteExecutor<'_>,
+  ) -> Result<Option<Self>, sqlx::Error> {
+    let name = name.as_ref();
+
+    sqlx::query_as("SELECT * FROM genera WHERE name = ?1")
+      .bind(name)
+      .fetch_optional(pool)
+      .await
+  }
+}
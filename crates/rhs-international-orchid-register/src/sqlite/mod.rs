@@ -0,0 +1,179 @@
+//! The same schema and queries as [`crate::sql`], in a single SQLite file. Queries are checked
+//! when they run rather than at compile time, so building doesn't need a database.
+
+use std::path::Path;
+
+use sqlx::{
+  sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+  SqlitePool,
+};
+
+pub mod details;
+pub mod genera;
+
+pub use crate::import::Import;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+  #[error("Not found")]
+  NotFound,
+  #[error(transparent)]
+  Sqlx(#[from] sqlx::Error),
+  #[error(transparent)]
+  Migrate(#[from] sqlx::migrate::MigrateError),
+}
+
+/// Opens the database at `path`, creating it if it doesn't exist.
+pub async fn connect(path: impl AsRef<Path>) -> Result<SqlitePool, Error> {
+  let options = SqliteConnectOptions::new()
+    .filename(path)
+    .create_if_missing(true)
+    .foreign_keys(true)
+    .journal_mode(SqliteJournalMode::Wal);
+
+  Ok(
+    SqlitePoolOptions::new()
+      .max_connections(1)
+      .connect_with(options)
+      .await?,
+  )
+}
+
+pub async fn migrate(pool: &SqlitePool) -> Result<(), Error> {
+  sqlx::migrate!("./migrations-sqlite").run(pool).await?;
+
+  Ok(())
+}
+
+/// Inserts `details`, parents and synonyms first.
+pub async fn import(
+  details: impl IntoIterator<Item = crate::api::Details>,
+  pool: &SqlitePool,
+  progress: impl FnMut(usize, usize),
+) -> Result<Import, Error> {
+  crate::import::ordered(details, progress, |value| async move {
+    // a parent or synonym that hasn't been inserted yet
+    match details::Details::insert(&value, pool).await {
+      Ok(_) => Ok(true),
+      Err(Error::NotFound) => Ok(false),
+      Err(e) => Err(e),
+    }
+  })
+  .await
+}
+
+#[tokio::test]
+async fn sqlite() -> Result<(), Error> {
+  use time::Month;
+
+  use crate::api::{Parent, Synonym};
+
+  let grex = |id: u32, name: &str, year: i32, seed: Option<&str>, pollen: Option<&str>| {
+    let parent = |epithet: &str| Parent {
+      genus: "Phalaenopsis".into(),
+      epithet: epithet.into(),
+    };
+
+    crate::api::Details {
+      id,
+      genus: "Phalaenopsis".into(),
+      epithet: name.into(),
+      synonym: false,
+      synonym_details: None,
+      registrant_name: None,
+      originator_name: None,
+      date_of_registration: Some(time::Date::from_calendar_date(year, Month::January, 1).unwrap()),
+      seed_parent: seed.map(parent),
+      pollen_parent: pollen.map(parent),
+    }
+  };
+
+  let pool = SqlitePoolOptions::new()
+    .max_connections(1)
+    .connect("sqlite::memory:")
+    .await?;
+  migrate(&pool).await?;
+
+  let import = import(
+    [
+      grex(
+        3,
+        "Elisabethae",
+        1927,
+        Some("amabilis"),
+        Some("schilleriana"),
+      ),
+      grex(4, "Doris", 1940, Some("Elisabethae"), Some("Elisabethae")),
+      grex(1, "amabilis", 1900, None, None),
+      grex(2, "schilleriana", 1900, None, None),
+      crate::api::Details {
+        synonym: true,
+        synonym_details: Some(Synonym {
+          genus: "Phalaenopsis".into(),
+          epithet: Some("Doris".into()),
+        }),
+        ..grex(5, "Doris Too", 1950, None, None)
+      },
+      grex(6, "Orphan", 1950, Some("Missing"), None),
+    ],
+    &pool,
+    |_, _| (),
+  )
+  .await?;
+
+  assert_eq!(import.inserted, 5);
+  assert_eq!(import.failed, [6]);
+
+  let amabilis = details::Details::get_from_registar_id(1, &pool)
+    .await?
+    .unwrap();
+  assert_eq!(amabilis.epithet, "amabilis");
+
+  let elisabethae = details::Details::get_from_name("Phalaenopsis", "Elisabethae", &pool)
+    .await?
+    .unwrap();
+  let (seed, pollen) = elisabethae.parents(&pool).await?;
+  assert_eq!(seed.unwrap().registar_id, 1);
+  assert_eq!(pollen.unwrap().registar_id, 2);
+  assert_eq!(
+    elisabethae.date_of_registration,
+    Some(time::Date::from_calendar_date(1927, Month::January, 1).unwrap())
+  );
+
+  let synonym = details::Details::get_from_registar_id(5, &pool)
+    .await?
+    .unwrap();
+  let (genus, epithet) = synonym.synonym_of(&pool).await?;
+  assert_eq!(genus.unwrap().name, "Phalaenopsis");
+  assert_eq!(epithet.unwrap().registar_id, 4);
+
+  let progeny = amabilis.progeny(None, &pool).await?;
+  let progeny = progeny
+    .iter()
+    .map(|d| (d.details.registar_id, d.generation))
+    .collect::<Vec<_>>();
+  assert_eq!(progeny, [(3, 1), (4, 2)]);
+
+  let counts = amabilis.progeny_counts_by_year(Some(1), &pool).await?;
+  assert_eq!(counts.len(), 1);
+  assert_eq!((counts[0].year, counts[0].count), (Some(1927), 1));
+
+  // a bad record making amabilis its own descendant mustn't loop forever
+  sqlx::query("UPDATE details SET seed_parent = ?1 WHERE id = ?2")
+    .bind(elisabethae.id)
+    .bind(amabilis.id)
+    .execute(&pool)
+    .await?;
+  let progeny = amabilis.progeny(None, &pool).await?;
+  let progeny = progeny
+    .iter()
+    .map(|d| (d.details.registar_id, d.generation))
+    .collect::<Vec<_>>();
+  assert_eq!(progeny, [(3, 1), (4, 2)]);
+
+  pool.close().await;
+  let res = self::import([grex(7, "Lost", 1960, None, None)], &pool, |_, _| ()).await;
+  assert!(matches!(res, Err(Error::Sqlx(_))));
+
+  Ok(())
+}
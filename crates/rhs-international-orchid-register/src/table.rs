@@ -0,0 +1,37 @@
+//! The two-column CSV tables checked in to `data/`.
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("line {line}: {message}")]
+pub struct ParseError {
+  pub line: usize,
+  pub message: String,
+}
+
+/// The rows of a table, after its header and comments, with their line numbers.
+pub(crate) fn rows(table: &str) -> Result<Vec<(usize, &str, &str)>, ParseError> {
+  table
+    .lines()
+    .enumerate()
+    .map(|(i, line)| (i + 1, line.trim()))
+    .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+    // the header
+    .skip(1)
+    .map(|(line, row)| {
+      let (a, b) = row.split_once(',').ok_or_else(|| ParseError {
+        line,
+        message: format!("expected two columns in {row:?}"),
+      })?;
+
+      Ok((line, a.trim(), b.trim()))
+    })
+    .collect()
+}
+
+#[test]
+fn rows_() {
+  let table = "# a comment\na,b\n\n1, 2\n# another\n3,4\n";
+  assert_eq!(rows(table).unwrap(), [(4, "1", "2"), (6, "3", "4")]);
+
+  let err = rows("a,b\nno columns\n").unwrap_err();
+  assert_eq!(err.line, 2);
+}